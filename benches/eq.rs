@@ -0,0 +1,75 @@
+//! Benchmarks for comparing MIME types to strings.
+//!
+//! Median times on an x86_64 Linux machine, before the comparison was rewritten as a single
+//! forward scan:
+//!
+//! ```text
+//! eq_str/essence          12.6 ns
+//! eq_str/suffix           14.9 ns
+//! eq_str/parameters       34.4 ns  (wrongly unequal)
+//! eq_str/mismatch         12.8 ns  (wrongly equal)
+//! ```
+//!
+//! After:
+//!
+//! ```text
+//! eq_str/essence          12.2 ns
+//! eq_str/suffix           14.4 ns
+//! eq_str/parameters       67.4 ns
+//! eq_str/mismatch         12.2 ns
+//! ```
+//!
+//! The old comparison gave up at the first parameter and ignored anything after the MIME type,
+//! which is why it was faster on those cases. Parsing the string and comparing the MIME types
+//! instead, for reference:
+//!
+//! ```text
+//! parse_eq/essence        159 ns
+//! parse_eq/suffix         196 ns
+//! parse_eq/parameters     630 ns
+//! parse_eq/mismatch       172 ns
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use mr_mime::Mime;
+
+/// The MIME types to compare, along with the string to compare them to.
+///
+/// With the tables enabled, the parsed names are interned like the ones in `constants`.
+const CASES: &[(&str, &str, &str)] = &[
+    ("essence", "text/plain", "text/plain"),
+    ("suffix", "image/svg+xml", "image/svg+xml"),
+    (
+        "parameters",
+        "text/html; charset=utf-8; level=1",
+        "text/html;charset=utf-8;level=1",
+    ),
+    ("mismatch", "application/json", "application/jsonp"),
+];
+
+fn eq_str(c: &mut Criterion) {
+    let mut group = c.benchmark_group("eq_str");
+
+    for &(name, mime, string) in CASES {
+        let mime = Mime::parse(mime).unwrap();
+        group.bench_function(name, |b| b.iter(|| black_box(mime) == black_box(string)));
+    }
+
+    group.finish();
+}
+
+fn parse_eq(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_eq");
+
+    for &(name, mime, string) in CASES {
+        let mime = Mime::parse(mime).unwrap();
+        group.bench_function(name, |b| {
+            b.iter(|| Mime::parse(black_box(string)) == Ok(mime))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, eq_str, parse_eq);
+criterion_main!(benches);
@@ -0,0 +1,38 @@
+//! Check that MIME types that compare equal hash the same.
+//!
+//! Besides the two arbitrary MIME types, this also compares each of them with the MIME type
+//! that is parsed back from it, which is equal but usually represented differently.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mr_mime::Mime;
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+fn hash(mime: &Mime<'_>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    mime.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn check(a: &Mime<'_>, b: &Mime<'_>) {
+    if a == b {
+        assert_eq!(hash(a), hash(b), "{:?} == {:?}", a, b);
+    }
+}
+
+fuzz_target!(|mimes: (Mime<'_>, Mime<'_>)| {
+    let (a, b) = mimes;
+    check(&a, &b);
+
+    for mime in [a, b] {
+        let text = mime.to_string();
+        let parsed = Mime::parse(&text).expect("arbitrary MIME types parse back");
+        assert_eq!(parsed, mime, "{}", text);
+        check(&parsed, &mime);
+        check(&parsed, &a);
+        check(&parsed, &b);
+    }
+});
@@ -1,7 +1,9 @@
 //! Generates the `segments.rs` file for interned strings.
 //!
 //! This uses the Apache HTTP server's mime.types file, stored in the
-//! parent directory of this file.
+//! parent directory of this file, along with the preferred.types file that
+//! picks the preferred extension of some of those types and the obsolete.types
+//! file that lists the types that should no longer be used.
 
 use fastrand::Rng;
 use heck::{AsShoutySnakeCase, AsSnakeCase, AsUpperCamelCase, ToUpperCamelCase};
@@ -25,13 +27,15 @@ fn main() -> io::Result<()> {
     let mut args = env::args_os().skip(1);
     let input = args.next().unwrap_or_else(|| "mime.types".into());
     let output = args.next().unwrap_or_else(|| "segments.rs".into());
+    let preferred = args.next().unwrap_or_else(|| "preferred.types".into());
+    let obsolete = args.next().unwrap_or_else(|| "obsolete.types".into());
 
     // Open the input file.
     let input = File::open(input)?;
     let input = BufReader::new(input);
 
     // Read MIME types from the file.
-    let mime_types = input
+    let mut mime_types = input
         .lines()
         .filter_map(|line| {
             line.map(|line| {
@@ -49,6 +53,41 @@ fn main() -> io::Result<()> {
         })
         .collect::<io::Result<Vec<_>>>()?;
 
+    // Move the preferred extensions to the front.
+    let preferred = BufReader::new(File::open(preferred)?);
+    for line in preferred.lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut parts = line.split_whitespace();
+        let (ty, ext) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(ty), Some(ext), None) => (ty, ext),
+            _ => panic!("malformed preferred extension: {}", line),
+        };
+
+        let mut found = false;
+        for mime in mime_types
+            .iter_mut()
+            .filter(|mime| mime.to_string().eq_ignore_ascii_case(ty))
+        {
+            let index = mime
+                .extensions
+                .iter()
+                .position(|e| e == ext)
+                .unwrap_or_else(|| panic!("{} is not an extension of {}", ext, ty));
+            let ext = mime.extensions.remove(index);
+            mime.extensions.insert(0, ext);
+            found = true;
+        }
+
+        if !found {
+            panic!("{} is not a known MIME type", ty);
+        }
+    }
+
     // Open the output file.
     let output = File::create(output)?;
     let mut output = BufWriter::new(output);
@@ -92,17 +131,35 @@ fn main() -> io::Result<()> {
 
     let mut existing_names = HashSet::new();
     let mut existing_types = HashSet::new();
+    let mut constants = vec![];
 
     // Write the primary types.
 
     writeln!(output, "{}/// Common MIME type prefixes.", Indent(1))?;
     writeln!(output, "{}pub mod types {{", Indent(1))?;
+    writeln!(
+        output,
+        "{}/// The `*` wildcard, matching any type.",
+        Indent(2)
+    )?;
+    writeln!(
+        output,
+        "{}pub const STAR: crate::Type<'static> = crate::Type(crate::Name::Interned(crate::TypeIntern::Star));",
+        Indent(2)
+    )?;
+    writeln!(output)?;
 
+    let mut names = vec![];
     for mime in &mime_types {
         if !existing_types.insert(mime.ty.clone()) {
             continue;
         }
 
+        names.push((
+            mime.ty.to_ascii_lowercase(),
+            AsShoutySnakeCase(&mime.ty).to_string(),
+        ));
+
         writeln!(output, "{}/// The `{}` MIME type.", Indent(2), mime.ty)?;
         writeln!(
             output,
@@ -114,13 +171,26 @@ fn main() -> io::Result<()> {
         writeln!(output)?;
     }
 
+    write_all_slice(&mut output, "crate::Type<'static>", "type prefix", names)?;
     writeln!(output, "{}}}", Indent(1))?;
     writeln!(output)?;
 
     // Write the subtypes.
     existing_types.clear();
+    let mut names = vec![];
     writeln!(output, "{}/// Common MIME subtypes.", Indent(1))?;
     writeln!(output, "{}pub mod subtypes {{", Indent(1))?;
+    writeln!(
+        output,
+        "{}/// The `*` wildcard, matching any subtype.",
+        Indent(2)
+    )?;
+    writeln!(
+        output,
+        "{}pub const STAR: crate::Subtype<'static> = crate::Subtype(crate::Name::Interned(crate::SubtypeIntern::Star));",
+        Indent(2)
+    )?;
+    writeln!(output)?;
 
     for mime in &mime_types {
         if mime
@@ -137,6 +207,11 @@ fn main() -> io::Result<()> {
             continue;
         }
 
+        names.push((
+            mime.subtype.to_ascii_lowercase(),
+            AsShoutySnakeCase(&mime.subtype).to_string(),
+        ));
+
         writeln!(
             output,
             "{}/// The `{}` MIME subtype.",
@@ -153,11 +228,13 @@ fn main() -> io::Result<()> {
         writeln!(output)?;
     }
 
+    write_all_slice(&mut output, "crate::Subtype<'static>", "subtype", names)?;
     writeln!(output, "{}}}", Indent(1))?;
     writeln!(output)?;
 
     // Write the suffixes.
     existing_types.clear();
+    let mut names = vec![];
     writeln!(output, "{}/// Common MIME suffixes.", Indent(1))?;
     writeln!(output, "{}pub mod suffixes {{", Indent(1))?;
 
@@ -177,6 +254,11 @@ fn main() -> io::Result<()> {
                 _ => {}
             }
 
+            names.push((
+                suffix.to_ascii_lowercase(),
+                AsShoutySnakeCase(suffix).to_string(),
+            ));
+
             writeln!(output, "{}/// The `{}` MIME suffix.", Indent(2), suffix)?;
             writeln!(
                 output,
@@ -189,36 +271,52 @@ fn main() -> io::Result<()> {
         }
     }
 
+    write_all_slice(&mut output, "crate::Suffix<'static>", "suffix", names)?;
     writeln!(output, "{}}}", Indent(1))?;
     writeln!(output)?;
 
+    // Write the wildcard MIME type.
+    writeln!(output, "{}/// `*/*`, matching any MIME type.", Indent(1))?;
+    writeln!(
+        output,
+        "{}pub const STAR_STAR: crate::Mime<'static> = crate::Mime {{",
+        Indent(1)
+    )?;
+    writeln!(output, "{}ty: types::STAR,", Indent(2))?;
+    writeln!(output, "{}subtype: subtypes::STAR,", Indent(2))?;
+    writeln!(output, "{}suffix: None,", Indent(2))?;
+    writeln!(
+        output,
+        "{}parameters: crate::Parameters::Slice(&[])",
+        Indent(2)
+    )?;
+    writeln!(output, "{}}};", Indent(1))?;
+    writeln!(output)?;
+    writeln!(
+        output,
+        "{}/// `*/*`, matching any MIME type. This is an alias for [`STAR_STAR`].",
+        Indent(1)
+    )?;
+    writeln!(
+        output,
+        "{}pub const WILDCARD: crate::Mime<'static> = STAR_STAR;",
+        Indent(1)
+    )?;
+    writeln!(output)?;
+
     for mime in &mime_types {
-        if mime
-            .subtype
-            .chars()
-            .next()
-            .filter(|c| c.is_ascii_alphabetic())
-            .is_none()
-        {
+        if !mime.has_constant() {
             continue;
         }
 
-        match mime
-            .suffix
-            .as_ref()
-            .map(|s| s.to_upper_camel_case().to_lowercase())
-            .as_deref()
-        {
-            Some("hdr") | Some("src") => continue,
-            _ => {}
-        }
-
         let name = mime.name();
 
         if !existing_names.insert(name.clone()) {
             continue;
         }
 
+        constants.push(mime);
+
         writeln!(output, "{}/// `{}`", Indent(1), mime,)?;
         writeln!(
             output,
@@ -259,7 +357,12 @@ fn main() -> io::Result<()> {
         writeln!(output)?;
 
         writeln!(output, "{}#[test]", Indent(1))?;
-        writeln!(output, "{}fn {}_parse() {{", Indent(1), AsSnakeCase(&name))?;
+        writeln!(
+            output,
+            "{}fn {}() {{",
+            Indent(1),
+            identifier(format!("{}_parse", AsSnakeCase(&name)))
+        )?;
 
         // Parse the MIME type as a string.
         let mime_txt = mime.to_string();
@@ -284,12 +387,127 @@ fn main() -> io::Result<()> {
         writeln!(output)?;
     }
 
+    // Write out every constant, in the same order as `Mime`'s `Ord` implementation.
+    let mut names = constants
+        .iter()
+        .map(|mime| {
+            (
+                (
+                    mime.ty.to_ascii_lowercase(),
+                    mime.subtype.to_ascii_lowercase(),
+                    mime.suffix.as_ref().map(|s| s.to_ascii_lowercase()),
+                ),
+                mime.name(),
+                mime.ty.to_upper_camel_case(),
+            )
+        })
+        .collect::<Vec<_>>();
+    names.sort_by(|a, b| a.0.cmp(&b.0));
+    writeln!(
+        output,
+        "{}/// Every MIME type in this module, in ascending order.",
+        Indent(1)
+    )?;
+    writeln!(
+        output,
+        "{}pub static ALL: &[crate::Mime<'static>] = &[",
+        Indent(1)
+    )?;
+    let mut type_ranges: Vec<(String, usize, usize)> = vec![];
+    for (i, (_, name, ty)) in names.into_iter().enumerate() {
+        writeln!(output, "{}{},", Indent(2), name)?;
+
+        // Keep track of where each type's constants start and end.
+        match type_ranges.last_mut() {
+            Some((last, _, end)) if *last == ty => *end = i + 1,
+            _ => type_ranges.push((ty, i, i + 1)),
+        }
+    }
+    writeln!(output, "{}];", Indent(1))?;
+    writeln!(output)?;
+
+    // Write the public lookup function.
+    write_lines(
+        &mut output,
+        1,
+        r#"/// Look up the constant registered for the essence of a MIME type.
+///
+/// The essence consists of the type, the subtype and optionally the suffix, without any
+/// parameters (e.g. `image/svg+xml`). The lookup is case-insensitive and goes straight through
+/// the interned names, so no dynamic `Mime` is created along the way.
+///
+/// `None` is returned if any of the parts is unknown, or if all of the parts are known but
+/// there is no constant for their combination.
+///
+/// ## Example
+///
+/// ```rust
+/// use mr_mime::constants;
+///
+/// assert_eq!(constants::lookup("Image/PNG"), Some(constants::IMAGE_PNG));
+/// assert_eq!(constants::lookup("text/png"), None);
+/// ```
+pub fn lookup(essence: &str) -> Option<crate::Mime<'static>> {
+    crate::lookup_constant(essence)
+}"#,
+    )?;
+
     writeln!(output, "}}")?;
+    writeln!(output)?;
+
+    // Write the mapping from interned parts to constants.
+    lookup_function(&mut output, &constants)?;
+    writeln!(output)?;
+
+    // Write the mapping from types to their constants.
+    type_function(&mut output, &type_ranges)?;
+    writeln!(output)?;
 
     // Write the "guess" method.
     guess_function(&mut output, &mime_types)?;
     writeln!(output)?;
 
+    // Write the mapping from interned parts to their extensions, the reverse of "guess".
+    extensions_function(&mut output, &mime_types)?;
+    writeln!(output)?;
+
+    // Write the check for registered suffixes.
+    registered_suffix_function(&mut output)?;
+    writeln!(output)?;
+
+    // Write the check for obsolete MIME types.
+    let obsolete = BufReader::new(File::open(obsolete)?);
+    let obsolete = obsolete
+        .lines()
+        .filter_map(|line| {
+            line.map(|line| {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    return None;
+                }
+
+                let mut parts = line.split_whitespace();
+                match (parts.next(), parts.next(), parts.next()) {
+                    (Some(ty), Some(status @ ("obsolete" | "historical")), None) => {
+                        Some((ty.to_ascii_lowercase(), status.to_upper_camel_case()))
+                    }
+                    _ => panic!("malformed obsolete type: {}", line),
+                }
+            })
+            .transpose()
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+    obsolete_function(&mut output, &obsolete)?;
+    writeln!(output)?;
+
+    // Write the check for unregistered MIME types.
+    unregistered_function(&mut output, &constants)?;
+    writeln!(output)?;
+
+    // Write the table of types that are known to compress well or not.
+    compressibility_function(&mut output)?;
+    writeln!(output)?;
+
     Ok(())
 }
 
@@ -313,7 +531,7 @@ fn write_mime_part(
         })
         .map(|name| (name, name.to_upper_camel_case()))
         .collect::<Vec<_>>();
-    types.sort_unstable_by(|a, b| a.1.cmp(&b.1));
+    types.sort_by(|a, b| a.1.cmp(&b.1));
     types.dedup_by(|a, b| a.1 == b.1);
 
     // Write out the enum.
@@ -453,7 +671,11 @@ fn write_mime_part(
 
     // Add a test for the string parser.
     writeln!(output, "#[test]")?;
-    writeln!(output, "fn {}_from_str() {{", AsSnakeCase(name))?;
+    writeln!(
+        output,
+        "fn {}() {{",
+        identifier(format!("{}_from_str", AsSnakeCase(name)))
+    )?;
 
     if has_star {
         writeln!(
@@ -517,32 +739,372 @@ fn write_mime_part(
     Ok(())
 }
 
+/// Write the `ALL` slice for a module of name constants.
+///
+/// `names` holds the lowercase name (used for sorting) and the constant name.
+fn write_all_slice(
+    out: &mut impl Write,
+    ty: &str,
+    kind: &str,
+    mut names: Vec<(String, String)>,
+) -> io::Result<()> {
+    names.sort_by(|a, b| a.0.cmp(&b.0));
+
+    writeln!(
+        out,
+        "{}/// Every MIME {} in this module, in ascending order.",
+        Indent(2),
+        kind
+    )?;
+    writeln!(out, "{}pub static ALL: &[{}] = &[", Indent(2), ty)?;
+    for (_, name) in names {
+        writeln!(out, "{}{},", Indent(3), name)?;
+    }
+    writeln!(out, "{}];", Indent(2))?;
+
+    Ok(())
+}
+
+/// Write the function mapping a type to the range of its constants in `constants::ALL`.
+fn type_function(out: &mut impl Write, ranges: &[(String, usize, usize)]) -> io::Result<()> {
+    writeln!(
+        out,
+        "pub(super) fn constants_for_type(ty: TypeIntern) -> &'static [crate::Mime<'static>] {{"
+    )?;
+    writeln!(out, "{}match ty {{", Indent(1))?;
+
+    for (ty, start, end) in ranges {
+        writeln!(
+            out,
+            "{}TypeIntern::{} => &constants::ALL[{}..{}],",
+            Indent(2),
+            ty,
+            start,
+            end
+        )?;
+    }
+
+    writeln!(out, "{}_ => &[],", Indent(2))?;
+    writeln!(out, "{}}}", Indent(1))?;
+    writeln!(out, "}}")?;
+
+    Ok(())
+}
+
+/// The structured syntax suffixes registered with IANA.
+///
+/// See <https://www.iana.org/assignments/media-type-structured-suffix/>.
+const REGISTERED_SUFFIXES: &[&str] = &[
+    "ber",
+    "cbor",
+    "cbor-seq",
+    "der",
+    "fastinfoset",
+    "gzip",
+    "json",
+    "json-seq",
+    "jwt",
+    "sqlite3",
+    "tlv",
+    "wbxml",
+    "xml",
+    "yaml",
+    "zip",
+    "zstd",
+];
+
+/// Write the function telling whether a suffix is registered.
+fn registered_suffix_function(out: &mut impl Write) -> io::Result<()> {
+    writeln!(
+        out,
+        "pub(super) fn is_registered_suffix(suffix: &str) -> bool {{"
+    )?;
+    writeln!(out, "{}const REGISTERED: &[&str] = &[", Indent(1))?;
+    for suffix in REGISTERED_SUFFIXES {
+        writeln!(out, "{}\"{}\",", Indent(2), suffix)?;
+    }
+    writeln!(out, "{}];", Indent(1))?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "{}REGISTERED.iter().any(|registered| registered.eq_ignore_ascii_case(suffix))",
+        Indent(1)
+    )?;
+    writeln!(out, "}}")?;
+
+    Ok(())
+}
+
+/// Write the function giving the status of a MIME type that should no longer be used.
+fn obsolete_function(out: &mut impl Write, obsolete: &[(String, String)]) -> io::Result<()> {
+    writeln!(
+        out,
+        "pub(super) fn obsolete_status(mime: &crate::Mime<'_>) -> Option<crate::IanaStatus> {{"
+    )?;
+    writeln!(
+        out,
+        "{}const OBSOLETE: &[(&str, crate::IanaStatus)] = &[",
+        Indent(1)
+    )?;
+    for (mime, status) in obsolete {
+        writeln!(
+            out,
+            "{}(\"{}\", crate::IanaStatus::{}),",
+            Indent(2),
+            mime,
+            status
+        )?;
+    }
+    writeln!(out, "{}];", Indent(1))?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "{}let essence = mime.essence_with_suffix();",
+        Indent(1)
+    )?;
+    writeln!(
+        out,
+        "{}OBSOLETE.iter().find(|(obsolete, _)| essence == *obsolete).map(|&(_, status)| status)",
+        Indent(1)
+    )?;
+    writeln!(out, "}}")?;
+
+    Ok(())
+}
+
+/// The top-level types that IANA registers subtypes under.
+///
+/// See <https://www.iana.org/assignments/top-level-media-types/>.
+const REGISTERED_TOP_LEVEL_TYPES: &[&str] = &[
+    "application",
+    "audio",
+    "example",
+    "font",
+    "haptics",
+    "image",
+    "message",
+    "model",
+    "multipart",
+    "text",
+    "video",
+];
+
+/// Write the function telling whether the interned parts are a type that was never registered.
+fn unregistered_function(out: &mut impl Write, mimes: &[&Mime]) -> io::Result<()> {
+    writeln!(
+        out,
+        "pub(super) fn is_unregistered_interned(ty: TypeIntern, subtype: SubtypeIntern, suffix: Option<SuffixIntern>) -> bool {{"
+    )?;
+    writeln!(out, "{}matches!(", Indent(1))?;
+    writeln!(out, "{}(ty, subtype, suffix),", Indent(2))?;
+
+    let mut existing = HashSet::new();
+    let mut first = true;
+    for mime in mimes.iter().filter(|mime| mime.is_unregistered()) {
+        let pattern = mime.pattern();
+        if !existing.insert(pattern.clone()) {
+            continue;
+        }
+
+        let separator = if first { "" } else { "| " };
+        writeln!(out, "{}{}{}", Indent(2), separator, pattern)?;
+        first = false;
+    }
+
+    writeln!(out, "{})", Indent(1))?;
+    writeln!(out, "}}")?;
+
+    Ok(())
+}
+
+/// MIME types that compress well, even though their top-level type says otherwise.
+///
+/// `text/*` is compressible by default, so text types don't need to be listed here.
+const COMPRESSIBLE_TYPES: &[&str] = &[
+    "application/ecmascript",
+    "application/graphql",
+    "application/javascript",
+    "application/json",
+    "application/postscript",
+    "application/rtf",
+    "application/sql",
+    "application/vnd.ms-fontobject",
+    "application/wasm",
+    "application/x-font-ttf",
+    "application/x-httpd-php",
+    "application/x-javascript",
+    "application/x-sh",
+    "application/x-tar",
+    "application/x-www-form-urlencoded",
+    "application/xml",
+    "application/yaml",
+    "font/otf",
+    "font/ttf",
+    "image/bmp",
+    "image/svg+xml",
+    "image/vnd.microsoft.icon",
+    "image/x-icon",
+];
+
+/// MIME types that are compressed already, outside of the top-level types that usually are.
+///
+/// `image/*`, `audio/*` and `video/*` are incompressible by default, so they don't need to be
+/// listed here.
+const INCOMPRESSIBLE_TYPES: &[&str] = &[
+    "application/gzip",
+    "application/pdf",
+    "application/vnd.rar",
+    "application/x-7z-compressed",
+    "application/x-bzip2",
+    "application/x-gzip",
+    "application/x-rar-compressed",
+    "application/x-xz",
+    "application/zip",
+    "application/zstd",
+    "font/woff",
+    "font/woff2",
+];
+
+/// Write the function telling whether a MIME type is known to compress well or not.
+fn compressibility_function(out: &mut impl Write) -> io::Result<()> {
+    writeln!(
+        out,
+        "pub(super) fn compressibility(mime: &crate::Mime<'_>) -> Option<crate::Compressibility> {{"
+    )?;
+    for (name, list) in [
+        ("COMPRESSIBLE", COMPRESSIBLE_TYPES),
+        ("INCOMPRESSIBLE", INCOMPRESSIBLE_TYPES),
+    ] {
+        writeln!(out, "{}const {}: &[&str] = &[", Indent(1), name)?;
+        for mime in list {
+            writeln!(out, "{}\"{}\",", Indent(2), mime)?;
+        }
+        writeln!(out, "{}];", Indent(1))?;
+    }
+    writeln!(out)?;
+    writeln!(
+        out,
+        "{}let essence = mime.essence_with_suffix();",
+        Indent(1)
+    )?;
+    writeln!(
+        out,
+        "{}if COMPRESSIBLE.iter().any(|&compressible| essence == compressible) {{",
+        Indent(1)
+    )?;
+    writeln!(
+        out,
+        "{}Some(crate::Compressibility::Compressible)",
+        Indent(2)
+    )?;
+    writeln!(
+        out,
+        "{}}} else if INCOMPRESSIBLE.iter().any(|&incompressible| essence == incompressible) {{",
+        Indent(1)
+    )?;
+    writeln!(
+        out,
+        "{}Some(crate::Compressibility::Incompressible)",
+        Indent(2)
+    )?;
+    writeln!(out, "{}}} else {{", Indent(1))?;
+    writeln!(out, "{}None", Indent(2))?;
+    writeln!(out, "{}}}", Indent(1))?;
+    writeln!(out, "}}")?;
+
+    Ok(())
+}
+
+/// Write the function mapping interned parts to their constant.
+fn lookup_function(out: &mut impl Write, mimes: &[&Mime]) -> io::Result<()> {
+    writeln!(
+        out,
+        "pub(super) fn lookup_interned(ty: TypeIntern, subtype: SubtypeIntern, suffix: Option<SuffixIntern>) -> Option<crate::Mime<'static>> {{"
+    )?;
+    writeln!(out, "{}match (ty, subtype, suffix) {{", Indent(1))?;
+
+    let mut existing = HashSet::new();
+
+    for mime in mimes {
+        let pattern = mime.pattern();
+
+        // Avoid emitting unreachable match arms.
+        if !existing.insert(pattern.clone()) {
+            continue;
+        }
+
+        writeln!(
+            out,
+            "{}{} => Some(constants::{}),",
+            Indent(2),
+            pattern,
+            mime.name()
+        )?;
+    }
+
+    writeln!(out, "{}_ => None,", Indent(2))?;
+    writeln!(out, "{}}}", Indent(1))?;
+    writeln!(out, "}}")?;
+
+    Ok(())
+}
+
+/// Write the function mapping interned parts to their file extensions.
+fn extensions_function(out: &mut impl Write, mimes: &[Mime]) -> io::Result<()> {
+    // A MIME type may be listed more than once, so merge the extensions of each listing.
+    let mut patterns: Vec<String> = vec![];
+    let mut extensions: HashMap<String, Vec<&str>> = HashMap::new();
+
+    for mime in mimes {
+        if !mime.has_constant() || mime.extensions.is_empty() {
+            continue;
+        }
+
+        let pattern = mime.pattern();
+        let entry = extensions.entry(pattern.clone()).or_insert_with(|| {
+            patterns.push(pattern);
+            vec![]
+        });
+        for ext in &mime.extensions {
+            if !entry.contains(&ext.as_str()) {
+                entry.push(ext);
+            }
+        }
+    }
+
+    writeln!(
+        out,
+        "pub(super) fn extensions_interned(ty: TypeIntern, subtype: SubtypeIntern, suffix: Option<SuffixIntern>) -> &'static [&'static str] {{"
+    )?;
+    writeln!(out, "{}match (ty, subtype, suffix) {{", Indent(1))?;
+
+    for pattern in &patterns {
+        writeln!(
+            out,
+            "{}{} => &{:?},",
+            Indent(2),
+            pattern,
+            extensions[pattern]
+        )?;
+    }
+
+    writeln!(out, "{}_ => &[],", Indent(2))?;
+    writeln!(out, "{}}}", Indent(1))?;
+    writeln!(out, "}}")?;
+
+    Ok(())
+}
+
 /// Write the "guess" function for MIME types.
 fn guess_function(out: &mut impl Write, mimes: &[Mime]) -> io::Result<()> {
     // We want a map between the extension and the MIME type, so reverse the slice.
     let mut map: HashMap<_, Vec<&Mime>> = HashMap::new();
 
     for mime in mimes {
-        if mime
-            .subtype
-            .chars()
-            .next()
-            .filter(|c| c.is_ascii_alphabetic())
-            .is_none()
-        {
+        if !mime.has_constant() {
             continue;
         }
 
-        match mime
-            .suffix
-            .as_ref()
-            .map(|s| s.to_upper_camel_case().to_lowercase())
-            .as_deref()
-        {
-            Some("hdr") | Some("src") => continue,
-            _ => {}
-        }
-
         for ext in &mime.extensions {
             match map.entry(ext) {
                 Entry::Occupied(mut entry) => {
@@ -555,6 +1117,11 @@ fn guess_function(out: &mut impl Write, mimes: &[Mime]) -> io::Result<()> {
         }
     }
 
+    // Put the most likely MIME type for each extension first.
+    for entries in map.values_mut() {
+        entries.sort_by_key(|mime| mime.popularity());
+    }
+
     // Create a case-insensitive string intern map.
     let mut builder = Builder::<_, IgnoreCase<Utf8Graph>>::new();
 
@@ -658,6 +1225,70 @@ impl Mime {
         })
     }
 
+    /// Sort key used to order the MIME types sharing an extension; lower is more popular.
+    ///
+    /// Types in the standards tree come first, followed by the vendor and personal trees, and
+    /// then by unregistered `x-` types. Within a tree, shorter types come first, and types of
+    /// the same length are ordered alphabetically so the result doesn't depend on the input.
+    fn popularity(&self) -> (u8, usize, String) {
+        let subtype = self.subtype.to_ascii_lowercase();
+        let tree = if subtype.starts_with("vnd.") || subtype.starts_with("prs.") {
+            1
+        } else if subtype.starts_with("x-") || subtype.starts_with("x.") {
+            2
+        } else {
+            0
+        };
+
+        let name = self.to_string().to_ascii_lowercase();
+        (tree, name.len(), name)
+    }
+
+    /// The pattern matching the interned parts of this MIME type.
+    fn pattern(&self) -> String {
+        let suffix = match self.suffix {
+            Some(ref suffix) => format!("Some(SuffixIntern::{})", AsUpperCamelCase(suffix)),
+            None => "None".to_string(),
+        };
+
+        format!(
+            "(TypeIntern::{}, SubtypeIntern::{}, {})",
+            AsUpperCamelCase(&self.ty),
+            AsUpperCamelCase(&self.subtype),
+            suffix
+        )
+    }
+
+    /// Tell whether this MIME type was never registered with IANA.
+    ///
+    /// `mime.types` doesn't say, so this goes by the name: types outside of the top-level types
+    /// that IANA registers, and subtypes with an `x-` or `x.` prefix, are unregistered.
+    fn is_unregistered(&self) -> bool {
+        let subtype = self.subtype.to_ascii_lowercase();
+        !REGISTERED_TOP_LEVEL_TYPES.contains(&self.ty.to_ascii_lowercase().as_str())
+            || subtype.starts_with("x-")
+            || subtype.starts_with("x.")
+    }
+
+    /// Tell whether this MIME type gets a constant, and so can be looked up by extension.
+    fn has_constant(&self) -> bool {
+        let subtype_is_ident = self
+            .subtype
+            .chars()
+            .next()
+            .filter(|c| c.is_ascii_alphabetic())
+            .is_some();
+        let suffix_is_skipped = matches!(
+            self.suffix
+                .as_ref()
+                .map(|s| s.to_upper_camel_case().to_lowercase())
+                .as_deref(),
+            Some("hdr") | Some("src")
+        );
+
+        subtype_is_ident && !suffix_is_skipped
+    }
+
     fn name(&self) -> String {
         format!(
             "{}_{}{}",
@@ -682,6 +1313,40 @@ impl fmt::Display for Mime {
     }
 }
 
+/// Words that are reserved in Rust, and can't be used as identifiers as they are.
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Turn a generated name into a valid identifier.
+///
+/// Keywords are written as raw identifiers, except for the few that can't be raw, which get
+/// a `_mime` suffix instead.
+fn identifier(name: String) -> String {
+    match name.as_str() {
+        "crate" | "self" | "Self" | "super" => format!("{}_mime", name),
+        keyword if KEYWORDS.contains(&keyword) => format!("r#{}", keyword),
+        _ => name,
+    }
+}
+
+/// Write a block of text, indenting every non-empty line.
+fn write_lines(out: &mut impl Write, indent: usize, text: &str) -> io::Result<()> {
+    for line in text.lines() {
+        if line.is_empty() {
+            writeln!(out)?;
+        } else {
+            writeln!(out, "{}{}", Indent(indent), line)?;
+        }
+    }
+
+    Ok(())
+}
+
 struct Indent(usize);
 
 impl fmt::Display for Indent {
@@ -0,0 +1,162 @@
+//! Derive macros for [`mr-mime`](https://docs.rs/mr-mime).
+//!
+//! Use these through the `proc-macro` feature of `mr-mime`, which re-exports them.
+
+#![forbid(
+    unsafe_code,
+    future_incompatible,
+    missing_copy_implementations,
+    missing_debug_implementations,
+    missing_docs
+)]
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, quote_spanned};
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Error, Expr, Lit, LitStr, Meta};
+
+/// Implement `HasMimeType` from a `#[mime = "..."]` attribute.
+///
+/// The attribute on the struct or enum gives the MIME type of the type, which is what
+/// `mime_type` returns. On an enum, variants may have their own attribute as well, which
+/// `value_mime_type` returns for values of that variant. Variants without one use the
+/// attribute on the enum itself.
+///
+/// The MIME type is checked at compile time in the same way as the `mime!` macro, so it may
+/// have parameters, and an invalid one fails to compile.
+///
+/// ## Example
+///
+/// ```rust
+/// use mr_mime::{constants, HasMimeType};
+///
+/// #[derive(HasMimeType)]
+/// #[mime = "text/html; charset=utf-8"]
+/// struct Page(String);
+///
+/// #[derive(HasMimeType)]
+/// #[mime = "application/octet-stream"]
+/// enum Asset {
+///     #[mime = "image/png"]
+///     Png(Vec<u8>),
+///     #[mime = "text/css"]
+///     Style { source: String },
+///     Other,
+/// }
+///
+/// assert_eq!(Page::mime_type().essence(), constants::TEXT_HTML);
+/// assert_eq!(Page::mime_type().get_parameter("charset"), Some(&b"utf-8"[..]));
+/// assert_eq!(Page("<p>hello</p>".into()).value_mime_type(), Page::mime_type());
+///
+/// assert_eq!(Asset::mime_type(), constants::APPLICATION_OCTET_STREAM);
+/// assert_eq!(Asset::Png(vec![]).value_mime_type(), constants::IMAGE_PNG);
+/// assert_eq!(Asset::Style { source: "".into() }.value_mime_type(), constants::TEXT_CSS);
+/// assert_eq!(Asset::Other.value_mime_type(), constants::APPLICATION_OCTET_STREAM);
+/// ```
+///
+/// Invalid MIME types are rejected by the compiler:
+///
+/// ```compile_fail
+/// #[derive(mr_mime::HasMimeType)]
+/// #[mime = "text /html"]
+/// struct Page;
+/// ```
+#[proc_macro_derive(HasMimeType, attributes(mime))]
+pub fn derive_has_mime_type(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Generate the `HasMimeType` implementation for an item.
+fn expand(input: DeriveInput) -> Result<TokenStream2, Error> {
+    let mime = mime_attribute(&input.attrs)?
+        .ok_or_else(|| Error::new_spanned(&input.ident, "missing `#[mime = \"...\"]` attribute"))?;
+    let default = mime_expr(&mime);
+
+    // Only enums with their own MIME types for some variants need to look at the value.
+    let value_mime_type = match &input.data {
+        Data::Struct(_) => None,
+        Data::Enum(data) => {
+            let mut overridden = false;
+            let arms = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    let mime = match mime_attribute(&variant.attrs)? {
+                        Some(mime) => {
+                            overridden = true;
+                            mime_expr(&mime)
+                        }
+                        None => default.clone(),
+                    };
+                    let ident = &variant.ident;
+                    Ok(quote!(Self::#ident { .. } => #mime,))
+                })
+                .collect::<Result<TokenStream2, Error>>()?;
+
+            match overridden {
+                true => Some(quote! {
+                    fn value_mime_type(&self) -> ::mr_mime::Mime<'static> {
+                        match *self { #arms }
+                    }
+                }),
+                false => None,
+            }
+        }
+        Data::Union(data) => {
+            return Err(Error::new(
+                data.union_token.span,
+                "`HasMimeType` can't be derived for unions",
+            ))
+        }
+    };
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::mr_mime::HasMimeType for #ident #ty_generics #where_clause {
+            fn mime_type() -> ::mr_mime::Mime<'static> {
+                #default
+            }
+
+            #value_mime_type
+        }
+    })
+}
+
+/// Find the `#[mime = "..."]` attribute among the attributes of an item.
+fn mime_attribute(attrs: &[Attribute]) -> Result<Option<LitStr>, Error> {
+    let mut found = None;
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("mime")) {
+        if found.is_some() {
+            return Err(Error::new_spanned(attr, "duplicate `#[mime]` attribute"));
+        }
+
+        let lit = match &attr.meta {
+            Meta::NameValue(meta) => match &meta.value {
+                Expr::Lit(expr) => match &expr.lit {
+                    Lit::Str(lit) => Some(lit.clone()),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        };
+
+        found =
+            Some(lit.ok_or_else(|| {
+                Error::new_spanned(attr, "expected `#[mime = \"type/subtype\"]`")
+            })?);
+    }
+
+    Ok(found)
+}
+
+/// Build the MIME type, pointing errors from checking it at the string literal.
+fn mime_expr(mime: &LitStr) -> TokenStream2 {
+    quote_spanned!(mime.span()=> ::mr_mime::mime!(#mime))
+}
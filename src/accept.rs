@@ -0,0 +1,815 @@
+//! Content negotiation using the HTTP `Accept` header.
+
+use crate::{
+    list::next_element, parameter_iter, parameters_from_bytes, BufferParameters, EitherIterator,
+    FormatParameterValue, Mime, Parameters, ParseError, SliceParameters, Subtype, Suffix, Type,
+};
+
+use core::fmt;
+use core::iter::FusedIterator;
+use core::str::{from_utf8, FromStr};
+
+/// The media range that matches anything, which stands in for an empty header or a bare `*`.
+const ANY: Mime<'static> = Mime::from_static("*/*");
+
+/// Parse the value of an HTTP `Accept` header.
+///
+/// This returns an iterator over the media ranges in the header, which are parsed lazily and
+/// without allocating. Commas inside of quoted strings don't split media ranges, and empty
+/// elements are skipped. A malformed media range is reported as an error, but the media ranges
+/// after it can still be read.
+///
+/// A header without any media ranges, such as an empty one, is treated like a missing header,
+/// which accepts anything: it yields a single `*/*`. A bare `*`, which some clients send, is
+/// read as `*/*` as well.
+///
+/// ## Example
+///
+/// ```rust
+/// use mr_mime::{ParseError, constants};
+/// use mr_mime::accept::{parse_accept, Quality};
+///
+/// let mut ranges = parse_accept("text/html, image/*;q=0.8, nonsense, , */*;q=0.1");
+///
+/// assert!(ranges.next().unwrap().unwrap().matches(&constants::TEXT_HTML));
+/// assert_eq!(ranges.next().unwrap().unwrap().quality(), Quality::new(800).unwrap());
+/// assert_eq!(ranges.next().unwrap(), Err(ParseError::NoSlash));
+/// assert!(ranges.next().unwrap().unwrap().r#type().is_star());
+/// assert!(ranges.next().is_none());
+/// ```
+pub fn parse_accept(header: &str) -> MediaRanges<'_> {
+    let rest = match next_element(&mut { header }) {
+        Some(_) => header,
+        None => "*/*",
+    };
+
+    MediaRanges { rest }
+}
+
+/// Iterator over the media ranges in an HTTP `Accept` header.
+///
+/// This is returned by [`parse_accept`].
+#[derive(Debug, Clone)]
+pub struct MediaRanges<'a> {
+    /// The part of the header that hasn't been parsed yet.
+    rest: &'a str,
+}
+
+impl<'a> Iterator for MediaRanges<'a> {
+    type Item = Result<MediaRange<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        next_element(&mut self.rest).map(MediaRange::parse)
+    }
+}
+
+impl FusedIterator for MediaRanges<'_> {}
+
+/// Choose the best of the `supported` MIME types for an HTTP `Accept` header.
+///
+/// This follows the precedence rules of RFC 9110. The weight of each supported MIME type comes
+/// from the most specific media range that matches it, so `text/html;q=0` excludes HTML even if
+/// the header also contains `*/*`. The supported MIME type with the highest weight wins, and ties
+/// go to whichever comes first in `supported`. MIME types with a weight of `0` are never chosen,
+/// and malformed media ranges are ignored.
+///
+/// This doesn't allocate. The header is parsed once for every supported MIME type.
+///
+/// ## Example
+///
+/// ```rust
+/// use mr_mime::constants;
+/// use mr_mime::accept::negotiate;
+///
+/// let supported = [constants::TEXT_HTML, constants::APPLICATION_JSON];
+///
+/// let best = negotiate(&supported, "application/json, text/html;q=0.9");
+/// assert_eq!(best, Some(&constants::APPLICATION_JSON));
+///
+/// let best = negotiate(&supported, "*/*;q=0.1, application/json;q=0");
+/// assert_eq!(best, Some(&constants::TEXT_HTML));
+///
+/// assert_eq!(negotiate(&supported, "image/*"), None);
+/// ```
+pub fn negotiate<'a, 'b>(supported: &'a [Mime<'b>], accept: &str) -> Option<&'a Mime<'b>> {
+    let ranges = parse_accept(accept);
+    let mut best: Option<(&'a Mime<'b>, Quality)> = None;
+
+    for mime in supported {
+        let quality = match quality_of(mime, ranges.clone()) {
+            Some(quality) if quality > Quality::MIN => quality,
+            _ => continue,
+        };
+
+        if best.map_or(true, |(_, best)| quality > best) {
+            best = Some((mime, quality));
+        }
+    }
+
+    best.map(|(mime, _)| mime)
+}
+
+/// Get the weight that the given media ranges assign to a MIME type.
+///
+/// Returns `None` if no media range matches.
+fn quality_of(mime: &Mime<'_>, ranges: MediaRanges<'_>) -> Option<Quality> {
+    let mut best: Option<((u8, usize), Quality)> = None;
+
+    for range in ranges.filter_map(Result::ok) {
+        if !range.matches(mime) {
+            continue;
+        }
+
+        let precedence = range.precedence();
+        if best.map_or(true, |(best, _)| precedence > best) {
+            best = Some((precedence, range.quality()));
+        }
+    }
+
+    best.map(|(_, quality)| quality)
+}
+
+/// A media range, as found in the HTTP `Accept` header.
+///
+/// A media range is a MIME type that may contain wildcards (like `*/*` or `text/*`), followed by
+/// a weight (the `q` parameter) and extension parameters. Parameters that come before the
+/// weight belong to the media range itself, while the ones after it are extension parameters.
+///
+/// ## Example
+///
+/// ```rust
+/// use mr_mime::{Mime, constants};
+/// use mr_mime::accept::MediaRange;
+///
+/// let range = MediaRange::parse("text/*; q=0.5").unwrap();
+///
+/// assert!(range.subtype().is_star());
+/// assert_eq!(range.quality().to_string(), "0.5");
+/// assert!(range.matches(&constants::TEXT_PLAIN));
+/// assert!(!range.matches(&constants::IMAGE_PNG));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct MediaRange<'a> {
+    /// The media range without the weight and the extension parameters.
+    mime: Mime<'a>,
+
+    /// The weight, if there is one.
+    quality: Option<Quality>,
+
+    /// The extension parameters following the weight.
+    extensions: &'a [u8],
+}
+
+impl<'a> MediaRange<'a> {
+    /// Parse a media range from a string.
+    ///
+    /// The weight must be a number between `0` and `1`. A bare `*` is read as `*/*`.
+    pub fn parse(source: &'a str) -> Result<Self, ParseError> {
+        let mime = match source.split_once(';') {
+            Some((essence, parameters)) if is_bare_star(essence) => Mime {
+                parameters: parameters_from_bytes(parameters.as_bytes())?,
+                ..ANY
+            },
+            None if is_bare_star(source) => ANY,
+            _ => Mime::parse(source)?,
+        };
+        let buffer = match mime.parameters {
+            Parameters::Buffer(buffer) => buffer,
+            Parameters::Slice(_) => {
+                return Ok(Self {
+                    mime,
+                    quality: None,
+                    extensions: &[],
+                })
+            }
+        };
+
+        // Look for the weight, which separates the parameters from the extension parameters.
+        let mut iter = parameter_iter(buffer);
+        loop {
+            let start = buffer.len() - iter.bytes.len();
+            let (key, value) = match iter.next() {
+                Some(param) => param,
+                None => break,
+            };
+
+            if key.eq_ignore_ascii_case(b"q") {
+                let quality = Quality::from_bytes(value)?;
+
+                return Ok(Self {
+                    mime: Mime {
+                        parameters: Parameters::Buffer(&buffer[..start]),
+                        ..mime
+                    },
+                    quality: Some(quality),
+                    extensions: iter.bytes,
+                });
+            }
+        }
+
+        Ok(Self {
+            mime,
+            quality: None,
+            extensions: &[],
+        })
+    }
+
+    /// Get the type of this media range, which may be `*`.
+    pub fn r#type(&self) -> Type<'a> {
+        self.mime.ty
+    }
+
+    /// Get the subtype of this media range, which may be `*`.
+    pub fn subtype(&self) -> Subtype<'a> {
+        self.mime.subtype
+    }
+
+    /// Get the suffix of this media range, if there is one.
+    pub fn suffix(&self) -> Option<Suffix<'a>> {
+        self.mime.suffix
+    }
+
+    /// Iterate over the parameters of this media range.
+    ///
+    /// This excludes the weight and the extension parameters that follow it.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mr_mime::accept::MediaRange;
+    ///
+    /// let range = MediaRange::parse("text/html; level=1; q=0.7; foo=bar").unwrap();
+    /// assert!(range.params().eq([("level", b"1".as_ref())].iter().copied()));
+    /// ```
+    pub fn params(&self) -> EitherIterator<SliceParameters<'_>, BufferParameters<'_>> {
+        self.mime.parameters()
+    }
+
+    /// Iterate over the extension parameters, which follow the weight.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mr_mime::accept::MediaRange;
+    ///
+    /// let range = MediaRange::parse("text/html; level=1; q=0.7; foo=bar").unwrap();
+    /// assert!(range.extensions().eq([("foo", b"bar".as_ref())].iter().copied()));
+    /// ```
+    pub fn extensions(&self) -> BufferParameters<'a> {
+        BufferParameters {
+            iter: parameter_iter(self.extensions),
+        }
+    }
+
+    /// Get the weight of this media range.
+    ///
+    /// This is [`Quality::MAX`] if no weight was given.
+    pub fn quality(&self) -> Quality {
+        self.quality.unwrap_or(Quality::MAX)
+    }
+
+    /// Tell whether the given MIME type falls within this media range.
+    ///
+    /// See [`Mime::matches`] for the exact rules.
+    pub fn matches(&self, mime: &Mime<'_>) -> bool {
+        mime.matches(&self.mime)
+    }
+
+    /// Get how specific this media range is, for ordering by precedence.
+    ///
+    /// Wildcards are less specific than concrete names, and media ranges with more parameters
+    /// are more specific than those with fewer.
+    fn precedence(&self) -> (u8, usize) {
+        let names = if self.mime.ty.is_star() {
+            0
+        } else if self.mime.subtype.is_star() {
+            1
+        } else {
+            2
+        };
+
+        (names, self.params().count())
+    }
+}
+
+impl fmt::Display for MediaRange<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.mime, f)?;
+
+        if let Some(quality) = self.quality {
+            write!(f, ";q={}", quality)?;
+        }
+
+        let mut extensions = parameter_iter(self.extensions);
+        while let Some((key, value)) = extensions.next() {
+            let key = from_utf8(key).unwrap_or_default();
+            if value.is_empty() {
+                write!(f, ";{}", key)?;
+            } else {
+                let escaped = extensions.quoted;
+                write!(f, ";{}={}", key, FormatParameterValue { value, escaped })?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl PartialEq for MediaRange<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.mime == other.mime
+            && self.quality() == other.quality()
+            && self.extensions().eq(other.extensions())
+    }
+}
+
+/// The weight of a media range, also known as its q-value.
+///
+/// Weights are numbers between `0` and `1` with at most three decimal places. They are stored
+/// as a whole number of thousandths, so they can be compared exactly and without using floats.
+///
+/// ## Example
+///
+/// ```rust
+/// use mr_mime::accept::Quality;
+///
+/// let quality: Quality = "0.125".parse().unwrap();
+/// assert_eq!(quality.thousandths(), 125);
+/// assert_eq!(quality.to_string(), "0.125");
+///
+/// assert!(Quality::MIN < quality && quality < Quality::MAX);
+/// assert!("1.5".parse::<Quality>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Quality(u16);
+
+impl Quality {
+    /// The highest weight, `1`.
+    pub const MAX: Quality = Quality(1000);
+
+    /// The lowest weight, `0`, which means "not acceptable".
+    pub const MIN: Quality = Quality(0);
+
+    /// Create a weight from a number of thousandths.
+    ///
+    /// Returns `None` if the weight would be greater than `1`.
+    pub fn new(thousandths: u16) -> Option<Self> {
+        if thousandths <= 1000 {
+            Some(Quality(thousandths))
+        } else {
+            None
+        }
+    }
+
+    /// Get the number of thousandths in this weight.
+    pub fn thousandths(self) -> u16 {
+        self.0
+    }
+
+    /// Parse a weight according to the `qvalue` grammar of RFC 9110.
+    fn from_bytes(value: &[u8]) -> Result<Self, ParseError> {
+        let (&first, rest) = value.split_first().ok_or(ParseError::InvalidQuality)?;
+        let decimals = match rest.split_first() {
+            None => &[][..],
+            Some((b'.', decimals)) if decimals.len() <= 3 => decimals,
+            Some(_) => return Err(ParseError::InvalidQuality),
+        };
+
+        let mut thousandths = match first {
+            b'0' => 0,
+            b'1' => 1000,
+            _ => return Err(ParseError::InvalidQuality),
+        };
+        let mut scale = 100;
+        for &digit in decimals {
+            match digit {
+                b'0'..=b'9' => thousandths += u16::from(digit - b'0') * scale,
+                _ => return Err(ParseError::InvalidQuality),
+            }
+            scale /= 10;
+        }
+
+        Quality::new(thousandths).ok_or(ParseError::InvalidQuality)
+    }
+}
+
+impl Default for Quality {
+    fn default() -> Self {
+        Quality::MAX
+    }
+}
+
+impl FromStr for Quality {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Quality::from_bytes(s.as_bytes())
+    }
+}
+
+impl fmt::Display for Quality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            1000 => f.write_str("1"),
+            0 => f.write_str("0"),
+            mut fraction => {
+                // Write out the digits, leaving off the trailing zeroes.
+                f.write_str("0.")?;
+                let mut scale = 100;
+                while fraction > 0 {
+                    let digit = fraction / scale;
+                    write!(f, "{}", digit)?;
+                    fraction -= digit * scale;
+                    scale /= 10;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Is this essence a lone `*`, as some clients send instead of `*/*`?
+fn is_bare_star(essence: &str) -> bool {
+    essence.trim_matches(|c| c == ' ' || c == '\t') == "*"
+}
+
+#[cfg(all(test, feature = "tables"))]
+mod media_range_test {
+    use super::*;
+    use crate::constants;
+    use core::str::from_utf8;
+
+    #[test]
+    fn media_range_parses_accept_elements() {
+        let mut ranges = "text/html, text/*;q=0.3, */*;q=0.1"
+            .split(',')
+            .map(|range| MediaRange::parse(range).unwrap());
+
+        let html = ranges.next().unwrap();
+        assert_eq!(html.r#type(), constants::types::TEXT);
+        assert_eq!(html.subtype(), constants::subtypes::HTML);
+        assert_eq!(html.quality().thousandths(), 1000);
+        assert!(html.matches(&constants::TEXT_HTML));
+        assert!(!html.matches(&constants::TEXT_PLAIN));
+
+        let text = ranges.next().unwrap();
+        assert_eq!(text.r#type(), constants::types::TEXT);
+        assert!(text.subtype().is_star());
+        assert_eq!(text.quality().thousandths(), 300);
+        assert!(text.matches(&constants::TEXT_HTML));
+        assert!(text.matches(&constants::TEXT_PLAIN));
+        assert!(!text.matches(&constants::IMAGE_PNG));
+
+        let any = ranges.next().unwrap();
+        assert!(any.r#type().is_star());
+        assert!(any.subtype().is_star());
+        assert_eq!(any.quality().thousandths(), 100);
+        assert!(any.matches(&constants::IMAGE_PNG));
+
+        assert!(ranges.next().is_none());
+    }
+
+    #[test]
+    fn parse_accept_handles_rfc_examples() {
+        let qualities = |header| {
+            let mut qualities = [0; 8];
+            let mut len = 0;
+            for (slot, range) in qualities.iter_mut().zip(parse_accept(header)) {
+                *slot = range.unwrap().quality().thousandths();
+                len += 1;
+            }
+            (qualities, len)
+        };
+
+        let (q, len) = qualities("audio/*; q=0.2, audio/basic");
+        assert_eq!(&q[..len], &[200, 1000]);
+
+        let (q, len) =
+            qualities("text/plain; q=0.5, text/html,\n       text/x-dvi; q=0.8, text/x-c");
+        assert_eq!(&q[..len], &[500, 1000, 800, 1000]);
+
+        let (q, len) = qualities("text/*, text/plain, text/plain;format=flowed, */*");
+        assert_eq!(&q[..len], &[1000; 4]);
+
+        let (q, len) = qualities(
+            "text/*;q=0.3, text/plain;q=0.7, text/plain;format=flowed,\
+             text/plain;format=fixed;q=0.4, */*;q=0.5",
+        );
+        assert_eq!(&q[..len], &[300, 700, 1000, 400, 500]);
+
+        let mut ranges = parse_accept("text/plain;format=fixed;q=0.4, */*;q=0.5");
+        let fixed = ranges.next().unwrap().unwrap();
+        assert!(fixed
+            .params()
+            .eq([("format", b"fixed".as_ref())].iter().copied()));
+        assert!(ranges
+            .next()
+            .unwrap()
+            .unwrap()
+            .matches(&constants::IMAGE_PNG));
+        assert!(ranges.next().is_none());
+    }
+
+    #[test]
+    fn parse_accept_respects_quoted_commas() {
+        let mut ranges = parse_accept("text/html;foo=\"a,\\\",b\";q=0.5, text/plain");
+
+        let html = ranges.next().unwrap().unwrap();
+        assert!(html
+            .params()
+            .eq([("foo", br#"a,\",b"#.as_ref())].iter().copied()));
+        assert_eq!(html.quality().thousandths(), 500);
+
+        let plain = ranges.next().unwrap().unwrap();
+        assert_eq!(plain.subtype(), constants::subtypes::PLAIN);
+        assert!(ranges.next().is_none());
+    }
+
+    #[test]
+    fn parse_accept_skips_empty_elements() {
+        let mut ranges = parse_accept(" ,text/html, ,\t,");
+        assert!(ranges
+            .next()
+            .unwrap()
+            .unwrap()
+            .matches(&constants::TEXT_HTML));
+        assert!(ranges.next().is_none());
+        assert!(ranges.next().is_none());
+    }
+
+    #[test]
+    fn parse_accept_without_ranges_accepts_anything() {
+        for header in &["", " ", " , ,"] {
+            let mut ranges = parse_accept(header);
+            let any = ranges.next().unwrap().unwrap();
+            assert!(any.r#type().is_star(), "{:?}", header);
+            assert!(any.subtype().is_star(), "{:?}", header);
+            assert_eq!(any.quality(), Quality::MAX, "{:?}", header);
+            assert!(ranges.next().is_none(), "{:?}", header);
+        }
+
+        let supported = [constants::TEXT_HTML, constants::APPLICATION_JSON];
+        assert_eq!(negotiate(&supported, ""), Some(&constants::TEXT_HTML));
+    }
+
+    #[test]
+    fn parse_accept_reads_bare_star_as_any() {
+        let mut ranges = parse_accept("*, text/html;q=0.5");
+        let any = ranges.next().unwrap().unwrap();
+        assert!(any.r#type().is_star());
+        assert!(any.subtype().is_star());
+        assert_eq!(any.quality(), Quality::MAX);
+        assert!(any.matches(&constants::IMAGE_PNG));
+        assert!(ranges
+            .next()
+            .unwrap()
+            .unwrap()
+            .matches(&constants::TEXT_HTML));
+        assert!(ranges.next().is_none());
+
+        let any = MediaRange::parse(" * ; q=0.2; foo=bar").unwrap();
+        assert!(any.r#type().is_star());
+        assert_eq!(any.quality().thousandths(), 200);
+        assert!(any
+            .extensions()
+            .eq([("foo", b"bar".as_ref())].iter().copied()));
+
+        assert_eq!(MediaRange::parse("**").unwrap_err(), ParseError::NoSlash);
+        assert_eq!(
+            MediaRange::parse("*;q=2").unwrap_err(),
+            ParseError::InvalidQuality
+        );
+    }
+
+    #[test]
+    fn parse_accept_reports_errors_per_element() {
+        let mut ranges = parse_accept("text, text/html;q=2, image/png");
+        assert_eq!(ranges.next(), Some(Err(ParseError::NoSlash)));
+        assert_eq!(ranges.next(), Some(Err(ParseError::InvalidQuality)));
+        assert!(ranges
+            .next()
+            .unwrap()
+            .unwrap()
+            .matches(&constants::IMAGE_PNG));
+        assert!(ranges.next().is_none());
+    }
+
+    #[test]
+    fn media_range_splits_parameters() {
+        let range = MediaRange::parse("text/html;level=1;Q=0.5;ext=\"a b\";flag").unwrap();
+
+        assert!(range
+            .params()
+            .eq([("level", b"1".as_ref())].iter().copied()));
+        assert!(range
+            .extensions()
+            .eq([("ext", b"a b".as_ref()), ("flag", b"".as_ref())]
+                .iter()
+                .copied()));
+        assert_eq!(range.quality().thousandths(), 500);
+        assert!(range.matches(&Mime::parse("text/html; level=1; charset=utf-8").unwrap()));
+        assert!(!range.matches(&constants::TEXT_HTML));
+    }
+
+    #[test]
+    fn negotiate_follows_rfc_9110_precedence() {
+        // The example from RFC 9110, section 12.5.1.
+        let accept = "text/*;q=0.3, text/plain;q=0.7, text/plain;format=flowed, \
+                      text/plain;format=fixed;q=0.4, */*;q=0.5";
+
+        for &(mime, expected) in &[
+            ("text/plain;format=flowed", 1000),
+            ("text/plain", 700),
+            ("text/html", 300),
+            ("image/jpeg", 500),
+            ("text/plain;format=fixed", 400),
+            ("text/html;level=3", 300),
+        ] {
+            let mime = Mime::parse(mime).unwrap();
+            let quality = quality_of(&mime, parse_accept(accept)).unwrap();
+            assert_eq!(quality.thousandths(), expected, "{}", mime);
+        }
+
+        let supported = [
+            Mime::parse("text/html").unwrap(),
+            Mime::parse("text/plain;format=fixed").unwrap(),
+            Mime::parse("text/plain;format=flowed").unwrap(),
+            constants::IMAGE_JPEG,
+        ];
+        assert_eq!(negotiate(&supported, accept), Some(&supported[2]));
+        assert_eq!(negotiate(&supported[..2], accept), Some(&supported[1]));
+        assert_eq!(negotiate(&supported[..1], accept), Some(&supported[0]));
+
+        // The example from RFC 9110, section 12.5.1, about audio.
+        let supported = [constants::AUDIO_MPEG, constants::AUDIO_BASIC];
+        let best = negotiate(&supported, "audio/*; q=0.2, audio/basic");
+        assert_eq!(best, Some(&constants::AUDIO_BASIC));
+    }
+
+    #[test]
+    fn negotiate_honors_exclusions() {
+        let supported = [constants::TEXT_HTML, constants::APPLICATION_JSON];
+
+        for accept in &[
+            "*/*;q=0.1, text/html;q=0",
+            "text/html;q=0, */*;q=0.1",
+            "text/html;q=0, text/*;q=1, */*;q=0.1",
+        ] {
+            assert_eq!(
+                negotiate(&supported, accept),
+                Some(&constants::APPLICATION_JSON),
+                "{}",
+                accept
+            );
+        }
+
+        assert_eq!(negotiate(&supported, "*/*;q=0"), None);
+        assert_eq!(
+            negotiate(&supported, "text/*, */*;q=0"),
+            Some(&supported[0])
+        );
+        assert_eq!(negotiate(&supported, "image/png, text/plain"), None);
+        assert_eq!(negotiate(&[], "*/*"), None);
+    }
+
+    #[test]
+    fn negotiate_breaks_ties_in_server_order() {
+        let supported = [constants::APPLICATION_JSON, constants::APPLICATION_XML];
+        assert_eq!(negotiate(&supported, "*/*"), Some(&supported[0]));
+        assert_eq!(
+            negotiate(&supported, "application/xml, application/json"),
+            Some(&supported[0])
+        );
+
+        let reversed = [supported[1], supported[0]];
+        assert_eq!(negotiate(&reversed, "*/*"), Some(&reversed[0]));
+
+        // Specificity beats the weight, then the weight beats the server order.
+        let supported = [constants::TEXT_HTML, constants::TEXT_PLAIN];
+        assert_eq!(
+            negotiate(&supported, "text/*, text/html;q=0.2"),
+            Some(&constants::TEXT_PLAIN)
+        );
+        assert_eq!(
+            negotiate(&supported, "text/html;q=0.5, text/plain;q=0.501"),
+            Some(&constants::TEXT_PLAIN)
+        );
+
+        // Malformed media ranges are skipped rather than stopping negotiation.
+        assert_eq!(
+            negotiate(&supported, "nonsense, text/html;q=2, text/plain;q=0.1"),
+            Some(&constants::TEXT_PLAIN)
+        );
+    }
+
+    #[test]
+    fn media_range_rejects_invalid_quality() {
+        for source in &[
+            "text/html;q=1.5",
+            "text/html;q=-1",
+            "text/html;q=high",
+            "text/html;q=0.1234",
+            "text/html;q=1.001",
+            "text/html;q=",
+            "text/html;q=.5",
+        ] {
+            assert_eq!(
+                MediaRange::parse(source),
+                Err(ParseError::InvalidQuality),
+                "{}",
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn quality_parses_boundaries() {
+        let parse = |s: &str| s.parse::<Quality>().map(Quality::thousandths);
+
+        assert_eq!(parse("0"), Ok(0));
+        assert_eq!(parse("0."), Ok(0));
+        assert_eq!(parse("0.000"), Ok(0));
+        assert_eq!(parse("0.001"), Ok(1));
+        assert_eq!(parse("0.5"), Ok(500));
+        assert_eq!(parse("0.999"), Ok(999));
+        assert_eq!(parse("1"), Ok(1000));
+        assert_eq!(parse("1.000"), Ok(1000));
+
+        for invalid in &["", "1.5", "1.001", "0.1234", "-1", "2", "0,5", ".5", "0.5 "] {
+            assert_eq!(
+                parse(invalid),
+                Err(ParseError::InvalidQuality),
+                "{}",
+                invalid
+            );
+        }
+
+        assert_eq!(Quality::new(1001), None);
+        assert_eq!(Quality::new(1000), Some(Quality::MAX));
+        assert_eq!(Quality::default(), Quality::MAX);
+        assert!(Quality::MIN < Quality::new(1).unwrap());
+    }
+
+    #[test]
+    fn quality_display_is_shortest_form() {
+        let mut buffer = [0u8; 8];
+
+        for &(thousandths, expected) in &[
+            (0, "0"),
+            (1, "0.001"),
+            (50, "0.05"),
+            (125, "0.125"),
+            (500, "0.5"),
+            (999, "0.999"),
+            (1000, "1"),
+        ] {
+            let quality = Quality::new(thousandths).unwrap();
+
+            let mut writer = Writer(&mut buffer, 0);
+            fmt::write(&mut writer, format_args!("{}", quality)).unwrap();
+            let len = writer.1;
+
+            let formatted = from_utf8(&buffer[..len]).unwrap();
+            assert_eq!(formatted, expected);
+            assert_eq!(formatted.parse(), Ok(quality));
+        }
+    }
+
+    #[test]
+    fn media_range_display_round_trips() {
+        let mut buffer = [0u8; 64];
+
+        for source in &[
+            "text/html",
+            "text/*;q=0.3",
+            "*/*;q=0.1",
+            "text/html;level=1;q=0.5;ext=a;flag",
+        ] {
+            let range = MediaRange::parse(source).unwrap();
+
+            let mut writer = Writer(&mut buffer, 0);
+            fmt::write(&mut writer, format_args!("{}", range)).unwrap();
+            let len = writer.1;
+
+            let formatted = from_utf8(&buffer[..len]).unwrap();
+            assert_eq!(formatted, *source);
+            assert_eq!(MediaRange::parse(formatted), Ok(range));
+        }
+    }
+
+    /// Writes into a fixed buffer.
+    struct Writer<'a>(&'a mut [u8], usize);
+
+    impl fmt::Write for Writer<'_> {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let end = self.1 + s.len();
+            self.0
+                .get_mut(self.1..end)
+                .ok_or(fmt::Error)?
+                .copy_from_slice(s.as_bytes());
+            self.1 = end;
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,220 @@
+//! Support for the `arbitrary` crate, for fuzzing code that takes MIME types.
+
+use crate::{is_http_codepoint, parameter_iter, Mime, Parameters, Subtype, Suffix, Type};
+#[cfg(feature = "alloc")]
+use crate::{MimeArg, MimeBuf, MimeCow};
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+/// Parameter lists to pick from, so that slices get compared with parsed buffers.
+const PARAMETERS: &[&[(&str, &[u8])]] = &[
+    &[],
+    &[("charset", b"utf-8")],
+    &[("charset", b"UTF-8")],
+    &[("q", b"0.5"), ("level", b"1")],
+    &[("level", b"1"), ("q", b"0.5")],
+];
+
+/// Every known MIME type, to pick from before making one up.
+#[cfg(feature = "tables")]
+const KNOWN: &[Mime<'static>] = crate::constants::ALL;
+#[cfg(not(feature = "tables"))]
+const KNOWN: &[Mime<'static>] = &[];
+
+/// Generates MIME types that can be written out with [`Display`](core::fmt::Display) and parsed
+/// back into an equal MIME type.
+///
+/// The essence is either a known MIME type or made up of names from the data. The parameters
+/// are either one of a few fixed lists, or taken from the data as a string that would be parsed,
+/// in which case every value must be a non-empty token.
+impl<'a> Arbitrary<'a> for Mime<'a> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mime = if !KNOWN.is_empty() && u.arbitrary()? {
+            *u.choose(KNOWN)?
+        } else {
+            Mime::new(
+                u.arbitrary::<Type<'a>>()?,
+                u.arbitrary::<Subtype<'a>>()?,
+                u.arbitrary::<Option<Suffix<'a>>>()?,
+                &[],
+            )
+        };
+
+        let parameters = if u.arbitrary()? {
+            Parameters::Slice(u.choose(PARAMETERS)?)
+        } else {
+            arbitrary_parameters(u)?
+        };
+
+        Ok(Mime { parameters, ..mime })
+    }
+}
+
+macro_rules! arbitrary_names {
+    ($($name: ident => $known: path),* $(,)?) => {
+        $(
+            /// Generates a known name, or a non-empty token made up from the data.
+            ///
+            /// Made up names never contain `+`, so that they don't run into a suffix.
+            impl<'a> Arbitrary<'a> for $name<'a> {
+                fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+                    #[cfg(feature = "tables")]
+                    let known: &[$name<'static>] = $known;
+                    #[cfg(not(feature = "tables"))]
+                    let known: &[$name<'static>] = &[];
+
+                    if !known.is_empty() && u.arbitrary()? {
+                        return u.choose(known).map(|&name| name);
+                    }
+
+                    let name = arbitrary_token(u)?;
+                    Ok($name::try_new(name).unwrap_or_else(|_| $name::from_static("x")))
+                }
+            }
+        )*
+    };
+}
+
+arbitrary_names! {
+    Type => crate::constants::types::ALL,
+    Subtype => crate::constants::subtypes::ALL,
+    Suffix => crate::constants::suffixes::ALL,
+}
+
+/// Take a string from the data, and keep the part of it that's a name.
+///
+/// This is empty if the string doesn't start with a name.
+fn arbitrary_token<'a>(u: &mut Unstructured<'a>) -> Result<&'a str> {
+    let s = <&'a str>::arbitrary(u)?;
+    let end = s
+        .bytes()
+        .position(|b| !is_http_codepoint(b) || b == b'+')
+        .unwrap_or(s.len());
+
+    Ok(&s[..end])
+}
+
+/// Take the parameters from the data, as a string that would follow the essence.
+///
+/// If the string has a parameter that isn't `key=value` with two non-empty tokens, there are
+/// no parameters instead, since quoted values keep their escapes when parsed, and wouldn't
+/// compare equal to the unescaped values that are written out.
+fn arbitrary_parameters<'a>(u: &mut Unstructured<'a>) -> Result<Parameters<'a>> {
+    let buffer = <&'a str>::arbitrary(u)?.as_bytes();
+    let is_token = |s: &[u8]| !s.is_empty() && s.iter().all(|&b| is_http_codepoint(b));
+
+    let mut iter = parameter_iter(buffer);
+    while let Some((key, value)) = iter.next() {
+        if !is_token(key) || !is_token(value) || iter.quoted {
+            return Ok(Parameters::Slice(&[]));
+        }
+    }
+
+    Ok(Parameters::Buffer(buffer))
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Arbitrary<'a> for MimeBuf {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Mime::arbitrary(u).map(MimeBuf::from)
+    }
+}
+
+/// Generates both borrowed and owned MIME types.
+#[cfg(feature = "alloc")]
+impl<'a> Arbitrary<'a> for MimeCow<'a> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mime = Mime::arbitrary(u)?;
+
+        if u.arbitrary()? {
+            Ok(MimeCow::from(mime))
+        } else {
+            Ok(MimeCow::from(MimeBuf::from(mime)))
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Arbitrary<'a> for MimeArg {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        MimeBuf::arbitrary(u).map(MimeArg)
+    }
+}
+
+#[cfg(all(test, feature = "std", feature = "tables"))]
+mod arbitrary_test {
+    use super::*;
+
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    use std::string::ToString;
+
+    fn hash(mime: &Mime<'_>) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        mime.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Some bytes that look like noise, with a few MIME-ish strings mixed in.
+    fn data(seed: u64) -> std::vec::Vec<u8> {
+        let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+        let mut data = std::vec::Vec::new();
+        for i in 0..512 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            if i % 64 == 0 {
+                data.extend_from_slice(b"\x10a=b; Charset=UTF-8");
+            }
+            data.push(state as u8);
+        }
+        data
+    }
+
+    #[test]
+    fn arbitrary_mime_display_round_trips() {
+        let mut with_parameters = 0;
+
+        for seed in 0..512 {
+            let data = data(seed);
+            let mut u = Unstructured::new(&data);
+            while let Ok(mime) = Mime::arbitrary(&mut u) {
+                if u.is_empty() {
+                    break;
+                }
+
+                let text = mime.to_string();
+                let parsed = Mime::parse(&text).unwrap_or_else(|err| panic!("{}: {}", err, text));
+                assert_eq!(parsed, mime, "{}", text);
+                assert_eq!(hash(&parsed), hash(&mime), "{}", text);
+                assert_eq!(parsed.to_string(), text);
+
+                with_parameters += (mime.parameters().count() > 0) as usize;
+            }
+        }
+
+        assert!(with_parameters > 0);
+    }
+
+    #[test]
+    fn arbitrary_mime_out_of_data() {
+        let mut u = Unstructured::new(&[]);
+        let mime = Mime::arbitrary(&mut u).unwrap();
+        assert_eq!(Mime::parse(&mime.to_string()), Ok(mime));
+    }
+
+    #[test]
+    fn arbitrary_wrappers_round_trip() {
+        let data = data(7);
+        let mut u = Unstructured::new(&data);
+
+        let buf = MimeBuf::arbitrary(&mut u).unwrap();
+        assert_eq!(buf.as_str().parse::<MimeBuf>().unwrap(), buf);
+
+        let arg = MimeArg::arbitrary(&mut u).unwrap();
+        assert_eq!(arg.to_string().parse::<MimeArg>().unwrap(), arg);
+
+        let cow = MimeCow::arbitrary(&mut u).unwrap();
+        assert_eq!(Mime::parse(&cow.to_string()).unwrap(), cow);
+    }
+}
@@ -0,0 +1,112 @@
+//! An owned MIME type for command-line arguments.
+
+use crate::{Mime, MimeBuf, ParseError};
+
+use core::fmt;
+use core::ops::Deref;
+use core::str::FromStr;
+
+/// An owned MIME type that can be parsed from a command-line argument.
+///
+/// Argument parsers like `clap` turn arguments into values with [`FromStr`], and need the
+/// result to own its text. This parses the argument into a [`MimeBuf`], and dereferences to it,
+/// so it can be used like one. With `clap`'s derive, a field of this type works without any
+/// extra attributes. Parse errors are [`ParseError`]s, which implement `std::error::Error` with
+/// the `std` feature.
+///
+/// This is only available with the `alloc` feature.
+///
+/// ## Example
+///
+/// ```rust
+/// use mr_mime::{constants, MimeArg};
+///
+/// let arg = "text/html".parse::<MimeArg>().unwrap();
+/// assert_eq!(arg.as_mime(), constants::TEXT_HTML);
+///
+/// assert!("html".parse::<MimeArg>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MimeArg(pub MimeBuf);
+
+impl MimeArg {
+    /// Turn this argument into the [`MimeBuf`] it holds.
+    pub fn into_inner(self) -> MimeBuf {
+        self.0
+    }
+}
+
+impl FromStr for MimeArg {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        MimeBuf::parse(s).map(MimeArg)
+    }
+}
+
+impl Deref for MimeArg {
+    type Target = MimeBuf;
+
+    fn deref(&self) -> &MimeBuf {
+        &self.0
+    }
+}
+
+impl From<MimeBuf> for MimeArg {
+    fn from(mime: MimeBuf) -> Self {
+        MimeArg(mime)
+    }
+}
+
+impl From<Mime<'_>> for MimeArg {
+    fn from(mime: Mime<'_>) -> Self {
+        MimeArg(mime.into())
+    }
+}
+
+impl From<MimeArg> for MimeBuf {
+    fn from(arg: MimeArg) -> Self {
+        arg.0
+    }
+}
+
+impl fmt::Display for MimeArg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+#[cfg(all(test, feature = "tables"))]
+mod arg_test {
+    use super::*;
+    use crate::constants;
+
+    use alloc::string::ToString;
+
+    #[test]
+    fn mime_arg_parses_owned() {
+        let arg = {
+            let source = "Image/SVG+xml; charset=\"utf-8\"".to_string();
+            source.parse::<MimeArg>().unwrap()
+        };
+
+        assert_eq!(
+            arg.as_mime().essence_with_suffix(),
+            constants::IMAGE_SVG_XML
+        );
+        assert_eq!(arg.as_str(), "Image/SVG+xml; charset=\"utf-8\"");
+        assert_eq!(arg.to_string(), arg.as_mime().to_string());
+        assert_eq!(arg.clone().into_inner(), arg.0);
+        assert_eq!(
+            MimeArg::from(constants::TEXT_PLAIN),
+            "text/plain".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn mime_arg_reports_parse_errors() {
+        assert_eq!("text".parse::<MimeArg>(), Err(ParseError::NoSlash));
+        assert_eq!("/plain".parse::<MimeArg>(), Err(ParseError::MissingType));
+        assert_eq!("".parse::<MimeArg>(), Err(ParseError::NoSlash));
+    }
+}
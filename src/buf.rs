@@ -0,0 +1,1665 @@
+//! An owned MIME type.
+
+use crate::{
+    is_http_quoted_codepoint, is_http_whitespace, is_token, parameter_iter, BufferParameters,
+    EitherIterator, FormatParameterValue, InvalidMime, Mime, Name, Parameters, ParamsError,
+    ParseError, SliceParameters, Subtype, SubtypeIntern, Suffix, SuffixIntern, Type, TypeIntern,
+};
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use core::cmp;
+use core::convert::TryFrom;
+use core::fmt::{self, Write};
+use core::hash::{Hash, Hasher};
+use core::str::FromStr;
+
+/// A MIME type that owns its storage.
+///
+/// This is the owned counterpart to [`Mime`]. The MIME type is kept as a single string, along
+/// with the positions of its components, so [`MimeBuf::as_mime`] is cheap. It compares, hashes
+/// and orders the same way as the equivalent [`Mime`].
+///
+/// ## Example
+///
+/// ```rust
+/// use mr_mime::{Mime, MimeBuf, constants};
+///
+/// let owned = MimeBuf::from(Mime::parse("text/plain; charset=utf-8").unwrap());
+///
+/// assert_eq!(owned.as_mime().essence(), constants::TEXT_PLAIN);
+/// assert_eq!(owned.to_string(), "text/plain;charset=utf-8");
+/// ```
+#[derive(Clone)]
+pub struct MimeBuf {
+    /// The MIME type as a string.
+    buffer: String,
+
+    /// The type of the MIME type.
+    ty: Segment<TypeIntern>,
+
+    /// The subtype of the MIME type.
+    subtype: Segment<SubtypeIntern>,
+
+    /// The suffix of the MIME type.
+    suffix: Option<Segment<SuffixIntern>>,
+
+    /// The position in the buffer where the parameters start, if there are any.
+    parameters: Option<usize>,
+}
+
+/// A name that is either interned or stored in the buffer.
+#[derive(Debug, Clone, Copy)]
+enum Segment<T> {
+    /// An interned name.
+    Interned(T),
+
+    /// A name stored in the buffer at the given range.
+    Range(usize, usize),
+}
+
+impl MimeBuf {
+    /// Create a new owned MIME type from its components.
+    ///
+    /// Each parameter comes with whether its value still has the escapes it was parsed with.
+    /// Values that aren't tokens are written out as quoted strings, with their quotes and
+    /// backslashes escaped unless they already are. Empty values are written as flags.
+    pub(crate) fn from_parts<'b>(
+        ty: Type<'_>,
+        subtype: Subtype<'_>,
+        suffix: Option<Suffix<'_>>,
+        parameters: impl Iterator<Item = (&'b str, &'b [u8], bool)>,
+    ) -> Self {
+        let mut buffer = String::new();
+
+        let ty = push_name(&mut buffer, ty.0);
+        buffer.push('/');
+        let subtype = push_name(&mut buffer, subtype.0);
+        let suffix = suffix.map(|suffix| {
+            buffer.push('+');
+            push_name(&mut buffer, suffix.0)
+        });
+
+        let mut start = None;
+        for (key, value, escaped) in parameters {
+            buffer.push(';');
+            start.get_or_insert(buffer.len());
+            buffer.push_str(key);
+
+            // Flag parameters don't have a value at all.
+            if value.is_empty() {
+                continue;
+            }
+
+            write!(buffer, "={}", FormatParameterValue { value, escaped }).ok();
+        }
+
+        Self {
+            buffer,
+            ty,
+            subtype,
+            suffix,
+            parameters: start,
+        }
+    }
+
+    /// Parse a MIME type, keeping the string as its storage.
+    ///
+    /// An owned [`String`] is used as it is, without copying it. Anything else is copied into a
+    /// new [`String`] first. The components are found by their positions in the string, so the
+    /// original text is kept exactly.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mr_mime::{Mime, MimeBuf, constants};
+    ///
+    /// let owned = MimeBuf::parse("application/json; charset=utf-8").unwrap();
+    /// assert_eq!(owned.essence(), constants::APPLICATION_JSON);
+    /// assert_eq!(owned, Mime::parse("application/json; charset=utf-8").unwrap());
+    ///
+    /// let owned: MimeBuf = "text/plain".parse().unwrap();
+    /// assert_eq!(owned, constants::TEXT_PLAIN);
+    /// ```
+    pub fn parse(source: impl Into<String>) -> Result<Self, ParseError> {
+        Self::try_from(source.into())
+    }
+
+    /// Borrow this MIME type as a [`Mime`].
+    pub fn as_mime(&self) -> Mime<'_> {
+        Mime {
+            ty: Type(self.name(self.ty)),
+            subtype: Subtype(self.name(self.subtype)),
+            suffix: self.suffix.map(|suffix| Suffix(self.name(suffix))),
+            parameters: match self.parameters {
+                None => Parameters::Slice(&[]),
+                Some(start) => Parameters::Buffer(&self.buffer.as_bytes()[start..]),
+            },
+        }
+    }
+
+    /// Get the string representation of this MIME type.
+    ///
+    /// This is the text it was parsed or built from, as it is. [`Display`](fmt::Display) writes
+    /// the same normalized form as [`Mime`] does instead.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mr_mime::MimeBuf;
+    ///
+    /// let owned = MimeBuf::parse("text/html; charset=utf-8").unwrap();
+    /// assert_eq!(owned.as_str(), "text/html; charset=utf-8");
+    /// assert_eq!(owned.to_string(), "text/html;charset=utf-8");
+    /// ```
+    pub fn as_str(&self) -> &str {
+        &self.buffer
+    }
+
+    /// Get the type of this MIME type.
+    ///
+    /// This and the other accessors work the same as the ones on [`Mime`].
+    pub fn r#type(&self) -> Type<'_> {
+        Type(self.name(self.ty))
+    }
+
+    /// Get the subtype of this MIME type.
+    pub fn subtype(&self) -> Subtype<'_> {
+        Subtype(self.name(self.subtype))
+    }
+
+    /// Get the suffix of this MIME type.
+    pub fn suffix(&self) -> Option<Suffix<'_>> {
+        self.suffix.map(|suffix| Suffix(self.name(suffix)))
+    }
+
+    /// Iterate over the parameters of this MIME type.
+    pub fn parameters(&self) -> EitherIterator<SliceParameters<'_>, BufferParameters<'_>> {
+        match self.parameters {
+            None => EitherIterator::Left([].iter().copied()),
+            Some(start) => EitherIterator::Right(BufferParameters {
+                iter: parameter_iter(&self.buffer.as_bytes()[start..]),
+            }),
+        }
+    }
+
+    /// Get the value of the first parameter with the given key, compared case-insensitively.
+    pub fn get_parameter(&self, key: &str) -> Option<&[u8]> {
+        self.parameters()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value)
+    }
+
+    /// Get the "essence" of this MIME type, without the suffix or the parameters.
+    pub fn essence(&self) -> Mime<'_> {
+        self.as_mime().essence()
+    }
+
+    /// Resolve a segment into a name.
+    fn name<T>(&self, segment: Segment<T>) -> Name<'_, T> {
+        match segment {
+            Segment::Interned(interned) => Name::Interned(interned),
+            Segment::Range(start, end) => Name::Dynamic(&self.buffer[start..end]),
+        }
+    }
+}
+
+/// Find a name that was parsed out of the buffer.
+fn find_name<T>(buffer: &str, name: Name<'_, T>) -> Segment<T> {
+    match name {
+        Name::Interned(interned) => Segment::Interned(interned),
+        Name::Dynamic(dynamic) => {
+            let start = dynamic.as_ptr() as usize - buffer.as_ptr() as usize;
+            Segment::Range(start, start + dynamic.len())
+        }
+    }
+}
+
+/// Write a name to the buffer, returning where to find it.
+fn push_name<T: Into<&'static str> + Copy>(buffer: &mut String, name: Name<'_, T>) -> Segment<T> {
+    match name {
+        Name::Interned(interned) => {
+            buffer.push_str(interned.into());
+            Segment::Interned(interned)
+        }
+        Name::Dynamic(dynamic) => {
+            let start = buffer.len();
+            buffer.push_str(dynamic);
+            Segment::Range(start, buffer.len())
+        }
+    }
+}
+
+impl<'a> From<Mime<'a>> for MimeBuf {
+    fn from(mime: Mime<'a>) -> Self {
+        Self::from_parts(
+            mime.ty,
+            mime.subtype,
+            mime.suffix,
+            mime.escaped_parameters(),
+        )
+    }
+}
+
+/// Parse a MIME type, keeping the string as its storage.
+///
+/// ## Example
+///
+/// ```rust
+/// use mr_mime::{MimeBuf, constants};
+/// use std::convert::TryFrom;
+///
+/// let owned = MimeBuf::try_from(String::from("text/html; charset=utf-8")).unwrap();
+/// assert_eq!(owned.as_mime().essence(), constants::TEXT_HTML);
+/// assert_eq!(String::from(owned), "text/html; charset=utf-8");
+/// ```
+impl TryFrom<String> for MimeBuf {
+    type Error = ParseError;
+
+    fn try_from(buffer: String) -> Result<Self, Self::Error> {
+        let mime = Mime::parse(&buffer)?;
+
+        let ty = find_name(&buffer, mime.ty.0);
+        let subtype = find_name(&buffer, mime.subtype.0);
+        let suffix = mime.suffix.map(|suffix| find_name(&buffer, suffix.0));
+        let parameters = match mime.parameters {
+            Parameters::Slice(_) => None,
+            Parameters::Buffer(params) => Some(params.as_ptr() as usize - buffer.as_ptr() as usize),
+        };
+
+        Ok(Self {
+            buffer,
+            ty,
+            subtype,
+            suffix,
+            parameters,
+        })
+    }
+}
+
+impl FromStr for MimeBuf {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl From<MimeBuf> for String {
+    fn from(mime: MimeBuf) -> Self {
+        mime.buffer
+    }
+}
+
+/// Writes out a MIME type like its `Display` implementation does, quoting the parameter values
+/// that aren't tokens, so the string parses back into the same parameters.
+impl From<Mime<'_>> for String {
+    fn from(mime: Mime<'_>) -> Self {
+        mime.to_string()
+    }
+}
+
+impl fmt::Debug for MimeBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.as_mime(), f)
+    }
+}
+
+/// Writes out a MIME type the same way as [`Mime`], so equal MIME types are written the same.
+/// [`MimeBuf::as_str`] gets the text as it was parsed.
+impl fmt::Display for MimeBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.as_mime(), f)
+    }
+}
+
+impl PartialEq for MimeBuf {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_mime() == other.as_mime()
+    }
+}
+
+impl Eq for MimeBuf {}
+
+impl PartialEq<Mime<'_>> for MimeBuf {
+    fn eq(&self, other: &Mime<'_>) -> bool {
+        self.as_mime() == *other
+    }
+}
+
+impl PartialEq<MimeBuf> for Mime<'_> {
+    fn eq(&self, other: &MimeBuf) -> bool {
+        *self == other.as_mime()
+    }
+}
+
+impl PartialOrd for MimeBuf {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for MimeBuf {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.as_mime().cmp(&other.as_mime())
+    }
+}
+
+impl Hash for MimeBuf {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_mime().hash(state);
+    }
+}
+
+impl<'a> Mime<'a> {
+    /// Copy this MIME type into a [`MimeBuf`], so that it no longer borrows anything.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mr_mime::{Mime, MimeBuf, constants};
+    ///
+    /// let owned: MimeBuf = {
+    ///     let source = String::from("text/plain; charset=utf-8");
+    ///     Mime::parse(&source).unwrap().to_owned_mime()
+    /// };
+    ///
+    /// assert_eq!(owned.essence(), constants::TEXT_PLAIN);
+    /// assert_eq!(owned.get_parameter("charset"), Some(b"utf-8".as_ref()));
+    /// ```
+    pub fn to_owned_mime(&self) -> MimeBuf {
+        MimeBuf::from(*self)
+    }
+
+    /// Copy this MIME type into a [`MimeBuf`] with its case normalized.
+    ///
+    /// The type, the subtype, the suffix and every parameter key are converted to ASCII
+    /// lowercase, and so is the value of any `charset` parameter, since character set names
+    /// are case-insensitive. Other parameter values are copied as they are, because their
+    /// case may matter (as it does for a multipart `boundary`). The parameters keep their
+    /// order, and nothing is added or removed.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mr_mime::Mime;
+    ///
+    /// let mime = Mime::parse("Text/X-Custom; Charset=UTF-8; Name=Report.TXT").unwrap();
+    /// assert_eq!(
+    ///     mime.to_ascii_lowercase().to_string(),
+    ///     "text/x-custom;charset=utf-8;name=Report.TXT"
+    /// );
+    /// ```
+    pub fn to_ascii_lowercase(&self) -> MimeBuf {
+        let ty = self.ty.into_str().to_ascii_lowercase();
+        let subtype = self.subtype.into_str().to_ascii_lowercase();
+        let suffix = self
+            .suffix
+            .map(|suffix| suffix.into_str().to_ascii_lowercase());
+
+        let parameters = self
+            .escaped_parameters()
+            .map(|(key, value, escaped)| {
+                let value = if key.eq_ignore_ascii_case("charset") {
+                    value.to_ascii_lowercase()
+                } else {
+                    value.to_vec()
+                };
+                (key.to_ascii_lowercase(), value, escaped)
+            })
+            .collect::<Vec<_>>();
+
+        // Lowercasing a name keeps it a valid name, so these never fall back.
+        MimeBuf::from_parts(
+            Type::new(&ty).unwrap_or(self.ty),
+            Subtype::new(&subtype).unwrap_or(self.subtype),
+            match &suffix {
+                Some(suffix) => Suffix::new(suffix).or(self.suffix),
+                None => None,
+            },
+            parameters
+                .iter()
+                .map(|(key, value, escaped)| (key.as_str(), value.as_slice(), *escaped)),
+        )
+    }
+
+    /// Get a copy of this MIME type with the given parameter set.
+    ///
+    /// If a parameter with the same key (compared case-insensitively) already exists, its value
+    /// is replaced. Otherwise, the parameter is added to the end. The value is taken literally,
+    /// and quoted with its quotes and backslashes escaped if it isn't a token.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`InvalidMime::ParameterKey`] if the key isn't a token, and
+    /// [`InvalidMime::ParameterValue`] if the value contains characters that can't be quoted,
+    /// like control characters. The index is the position the parameter would have taken.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mr_mime::{InvalidMime, Mime, constants};
+    ///
+    /// let html = constants::TEXT_HTML.with_param("charset", "utf-8").unwrap();
+    /// assert_eq!(html, Mime::parse("text/html; charset=utf-8").unwrap());
+    ///
+    /// let html = html.as_mime().with_param("CHARSET", "us-ascii").unwrap();
+    /// assert_eq!(html.to_string(), "text/html;charset=us-ascii");
+    ///
+    /// let html = html.as_mime().with_param("title", r#"say "hi""#).unwrap();
+    /// assert_eq!(html.as_str(), r#"text/html;charset=us-ascii;title="say \"hi\"""#);
+    ///
+    /// let html = html.as_mime().with_param("title", "a\r\nSet-Cookie: b");
+    /// assert_eq!(html, Err(InvalidMime::ParameterValue(1)));
+    /// ```
+    pub fn with_param(self, key: &str, value: &str) -> Result<MimeBuf, InvalidMime> {
+        check_parameter(key, value, self.parameter_index(key))?;
+        self.merge_params(&[(key, value)])
+    }
+
+    /// Get a copy of this MIME type with the given parameters merged in.
+    ///
+    /// Parameters with the same key as one in `extra` (compared case-insensitively) get the
+    /// new value, and keep their place. The remaining parameters from `extra` are added to the
+    /// end. If a key shows up in `extra` more than once, the last value wins. Values are taken
+    /// literally, like in [`Mime::with_param`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`InvalidMime::ParameterKey`] if a key isn't a token, and
+    /// [`InvalidMime::ParameterValue`] if a value contains characters that can't be quoted. The
+    /// index is the position of the first such pair in `extra`.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mr_mime::{InvalidMime, Mime};
+    ///
+    /// let base = Mime::parse("text/plain; charset=us-ascii").unwrap();
+    /// let merged = base.merge_params(&[("format", "flowed"), ("Charset", "utf-8")]).unwrap();
+    /// assert_eq!(merged.to_string(), "text/plain;charset=utf-8;format=flowed");
+    ///
+    /// let merged = base.merge_params(&[("format", "flowed"), ("bad key", "v")]);
+    /// assert_eq!(merged, Err(InvalidMime::ParameterKey(1)));
+    /// ```
+    pub fn merge_params(self, extra: &[(&str, &str)]) -> Result<MimeBuf, InvalidMime> {
+        for (i, &(key, value)) in extra.iter().enumerate() {
+            check_parameter(key, value, i)?;
+        }
+
+        let value_for = |key: &str| {
+            extra
+                .iter()
+                .rev()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|&(_, v)| v.as_bytes())
+        };
+
+        let parameters = self
+            .escaped_parameters()
+            .map(|(k, v, escaped)| match value_for(k) {
+                Some(value) => (k, value, false),
+                None => (k, v, escaped),
+            });
+
+        let added = extra.iter().enumerate().filter_map(|(i, &(key, value))| {
+            let overridden = extra[i + 1..]
+                .iter()
+                .any(|(k, _)| k.eq_ignore_ascii_case(key));
+
+            if overridden || self.get_parameter(key).is_some() {
+                None
+            } else {
+                Some((key, value.as_bytes(), false))
+            }
+        });
+
+        Ok(MimeBuf::from_parts(
+            self.ty,
+            self.subtype,
+            self.suffix,
+            parameters.chain(added),
+        ))
+    }
+
+    /// Get a copy of this MIME type with its parameters replaced by the given ones.
+    ///
+    /// The parameters can come from any collection of string pairs, like a `HashMap` or a
+    /// list of form fields. The ones that were already there are dropped. Keys are compared
+    /// case-insensitively, and if a key shows up more than once, the last value wins, in the
+    /// place where the key first showed up. Values are taken literally, like in
+    /// [`Mime::with_param`].
+    ///
+    /// Since the parameters have to live somewhere, this returns a [`MimeBuf`].
+    ///
+    /// ## Errors
+    ///
+    /// Every pair is checked, even one whose value is later replaced. Nothing is dropped or
+    /// fixed up: the first pair whose key isn't a token gives [`InvalidMime::ParameterKey`], and
+    /// the first whose value contains characters that can't be quoted gives
+    /// [`InvalidMime::ParameterValue`]. The index is the position of the pair in `map`.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mr_mime::{constants, InvalidMime};
+    ///
+    /// let fields = vec![
+    ///     ("charset".to_string(), "us-ascii".to_string()),
+    ///     ("format".to_string(), "flowed".to_string()),
+    ///     ("Charset".to_string(), "utf-8".to_string()),
+    /// ];
+    ///
+    /// let mime = constants::TEXT_PLAIN.with_parameters_from_map(fields).unwrap();
+    /// assert_eq!(mime.to_string(), "text/plain;charset=utf-8;format=flowed");
+    ///
+    /// let bad = constants::TEXT_PLAIN.with_parameters_from_map([("a", "1"), ("b c", "2")]);
+    /// assert_eq!(bad, Err(InvalidMime::ParameterKey(1)));
+    /// ```
+    pub fn with_parameters_from_map<K, V>(
+        self,
+        map: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<MimeBuf, InvalidMime>
+    where
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let map = map.into_iter().collect::<Vec<_>>();
+        for (i, (key, value)) in map.iter().enumerate() {
+            check_parameter(key.as_ref(), value.as_ref(), i)?;
+        }
+
+        let parameters = map.iter().enumerate().filter_map(|(i, (key, _))| {
+            let key = key.as_ref();
+            if map[..i]
+                .iter()
+                .any(|(k, _)| k.as_ref().eq_ignore_ascii_case(key))
+            {
+                return None;
+            }
+
+            let (_, value) = map
+                .iter()
+                .rev()
+                .find(|(k, _)| k.as_ref().eq_ignore_ascii_case(key))?;
+            Some((key, value.as_ref().as_bytes(), false))
+        });
+
+        Ok(MimeBuf::from_parts(
+            self.ty,
+            self.subtype,
+            self.suffix,
+            parameters,
+        ))
+    }
+
+    /// Get a copy of this MIME type with the value of one parameter replaced.
+    ///
+    /// The first parameter with the same key (compared case-insensitively) gets the new value
+    /// and keeps its place, while every other parameter stays as it is, even if it has the same
+    /// key. If there is no such parameter, it is added to the end. This differs from
+    /// [`Mime::with_param`], which replaces the value of every parameter with the same key.
+    ///
+    /// Since the new value has to live somewhere, this returns a [`MimeBuf`].
+    ///
+    /// ## Errors
+    ///
+    /// The key and value are checked like in [`Mime::with_param`], so this returns
+    /// [`InvalidMime::ParameterKey`] if the key isn't a token, and
+    /// [`InvalidMime::ParameterValue`] if the value contains characters that can't be quoted.
+    /// The index is the position of the parameter that would have been overwritten or added.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mr_mime::{InvalidMime, Mime};
+    ///
+    /// let mime = Mime::parse("text/plain; charset=us-ascii; format=flowed").unwrap();
+    /// let mime = mime.overwrite_parameter("charset", "utf-8").unwrap();
+    /// assert_eq!(mime.to_string(), "text/plain;charset=utf-8;format=flowed");
+    ///
+    /// let bad = mime.as_mime().overwrite_parameter("format", "a\r\nb");
+    /// assert_eq!(bad, Err(InvalidMime::ParameterValue(1)));
+    /// ```
+    pub fn overwrite_parameter(self, key: &str, new_value: &str) -> Result<MimeBuf, InvalidMime> {
+        let position = self
+            .parameters()
+            .position(|(k, _)| k.eq_ignore_ascii_case(key));
+        let index = position.unwrap_or_else(|| self.parameters().count());
+        check_parameter(key, new_value, index)?;
+
+        let parameters = self
+            .escaped_parameters()
+            .enumerate()
+            .map(|(i, (k, v, escaped))| {
+                if Some(i) == position {
+                    (k, new_value.as_bytes(), false)
+                } else {
+                    (k, v, escaped)
+                }
+            });
+
+        let added = match position {
+            Some(_) => None,
+            None => Some((key, new_value.as_bytes(), false)),
+        };
+
+        Ok(MimeBuf::from_parts(
+            self.ty,
+            self.subtype,
+            self.suffix,
+            parameters.chain(added),
+        ))
+    }
+
+    /// Get a copy of this MIME type with the given parameter set, checking it first.
+    ///
+    /// This works like [`Mime::with_param`]: parameters with the same key (compared
+    /// case-insensitively) get the new value, and otherwise the parameter is added to the end.
+    /// The other parameters keep their order. The value is taken literally, so quotes and
+    /// backslashes in it are escaped when it's written out.
+    ///
+    /// ## Errors
+    ///
+    /// The key and value are checked the same way as in [`Mime::try_new`], so this returns
+    /// [`InvalidMime::ParameterKey`] if the key isn't a token, and
+    /// [`InvalidMime::ParameterValue`] if the value contains characters that can't be quoted.
+    /// The index is the position the parameter would have taken.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mr_mime::{InvalidMime, Mime};
+    ///
+    /// let mime = Mime::parse("text/plain; format=flowed").unwrap();
+    /// let mime = mime.with_parameter("charset", "utf-8").unwrap();
+    /// assert_eq!(mime.to_string(), "text/plain;format=flowed;charset=utf-8");
+    ///
+    /// let title = mime.as_mime().with_parameter("title", r#"a "b" \c"#).unwrap();
+    /// assert_eq!(
+    ///     title.as_str(),
+    ///     r#"text/plain;format=flowed;charset=utf-8;title="a \"b\" \\c""#
+    /// );
+    ///
+    /// let mime = mime.as_mime().with_parameter("charset", "a\r\nb");
+    /// assert_eq!(mime, Err(InvalidMime::ParameterValue(1)));
+    /// ```
+    pub fn with_parameter(&self, key: &str, value: &str) -> Result<MimeBuf, InvalidMime> {
+        check_parameter(key, value, self.parameter_index(key))?;
+        self.merge_params(&[(key, value)])
+    }
+
+    /// The position of the first parameter with this key, or the number of parameters if there
+    /// is none.
+    fn parameter_index(&self, key: &str) -> usize {
+        self.parameters()
+            .position(|(k, _)| k.eq_ignore_ascii_case(key))
+            .unwrap_or_else(|| self.parameters().count())
+    }
+
+    /// Get a copy of this MIME type without the given parameter.
+    ///
+    /// Every parameter with the same key (compared case-insensitively) is removed, and the
+    /// others keep their order. If there is no such parameter, the copy is the same as this
+    /// MIME type.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mr_mime::Mime;
+    ///
+    /// let mime = Mime::parse("multipart/form-data; boundary=abc; charset=utf-8").unwrap();
+    /// let mime = mime.without_parameter("Boundary");
+    /// assert_eq!(mime.to_string(), "multipart/form-data;charset=utf-8");
+    /// ```
+    pub fn without_parameter(&self, key: &str) -> MimeBuf {
+        let parameters = self
+            .escaped_parameters()
+            .filter(|(k, _, _)| !k.eq_ignore_ascii_case(key));
+
+        MimeBuf::from_parts(self.ty, self.subtype, self.suffix, parameters)
+    }
+
+    /// Create a MIME type from components that have already been split apart.
+    ///
+    /// The iterator yields the type, then the subtype (which may end in a `+suffix`), then
+    /// alternating parameter keys and values. A key without a value at the end is taken as a
+    /// flag parameter. Values are taken literally, and quoted with their quotes and backslashes
+    /// escaped if they aren't tokens. Since the components don't come from one contiguous
+    /// string, they're copied into a [`MimeBuf`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`ParseError::MissingType`] or [`ParseError::MissingSubtype`] if the iterator
+    /// ends early or yields an empty name where the type or subtype should be, and
+    /// [`ParseError::NonHttpCodepoints`] if a component contains characters it can't contain.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mr_mime::{Mime, ParseError, constants};
+    ///
+    /// let parts = ["image", "svg+xml", "charset", "utf-8"];
+    /// let mime = Mime::parse_from_iterator(parts.iter().copied()).unwrap();
+    /// assert_eq!(mime.as_mime().essence_with_suffix(), constants::IMAGE_SVG_XML);
+    /// assert_eq!(mime.to_string(), "image/svg+xml;charset=utf-8");
+    ///
+    /// let parts = ["text"];
+    /// assert_eq!(
+    ///     Mime::parse_from_iterator(parts.iter().copied()),
+    ///     Err(ParseError::MissingSubtype)
+    /// );
+    /// ```
+    pub fn parse_from_iterator<'b>(
+        parts: impl IntoIterator<Item = &'b str>,
+    ) -> Result<MimeBuf, ParseError> {
+        let mut parts = parts.into_iter();
+
+        let ty = match parts.next() {
+            None | Some("") => return Err(ParseError::MissingType),
+            Some(ty) => Type::new(ty).ok_or(ParseError::NonHttpCodepoints)?,
+        };
+
+        // Per RFC 6839, the suffix is whatever follows the last plus sign.
+        let (subtype, suffix) = match parts.next() {
+            None => return Err(ParseError::MissingSubtype),
+            Some(subtype) => match subtype.rfind('+') {
+                Some(plus) => (&subtype[..plus], Some(&subtype[plus + 1..])),
+                None => (subtype, None),
+            },
+        };
+        if subtype.is_empty() {
+            return Err(ParseError::MissingSubtype);
+        }
+        let subtype = Subtype::new(subtype).ok_or(ParseError::NonHttpCodepoints)?;
+        let suffix = match suffix {
+            Some(suffix) if is_token(suffix) => Suffix::new(suffix),
+            Some(_) => return Err(ParseError::NonHttpCodepoints),
+            None => None,
+        };
+
+        let mut parameters = Vec::new();
+        while let Some(key) = parts.next() {
+            let value = parts.next().unwrap_or("");
+            if !is_token(key) || !value.bytes().all(is_http_quoted_codepoint) {
+                return Err(ParseError::NonHttpCodepoints);
+            }
+
+            parameters.push((key, value.as_bytes(), false));
+        }
+
+        Ok(MimeBuf::from_parts(
+            ty,
+            subtype,
+            suffix,
+            parameters.iter().copied(),
+        ))
+    }
+
+    /// Parse a MIME type leniently, recovering from problems that [`Mime::parse`] rejects.
+    ///
+    /// Along with the MIME type, this returns every problem that was found, in order, which is
+    /// useful for linting `Content-Type` values. The problems that can be recovered from are:
+    ///
+    /// - [`ParseError::StrayWhitespace`], for whitespace around the slash, the plus sign or the
+    ///   equals sign of a parameter, or after a parameter value. It is removed.
+    /// - [`ParseError::InvalidToken`], for an empty suffix. The suffix is left out.
+    /// - [`ParseError::InvalidParameter`] with [`ParamsError::MissingEquals`], for a parameter
+    ///   without a value. It is kept as a flag parameter.
+    /// - [`ParseError::InvalidParameter`] with [`ParamsError::UnterminatedQuote`], for a quoted
+    ///   string that is never closed. The rest of the string is taken as its value, up to the
+    ///   first character that can't be in a quoted string, like a line break.
+    /// - [`ParseError::InvalidParameter`] with any other [`ParamsError`], for a parameter with
+    ///   an invalid key or value. It is left out, unless only the text after its quoted value is
+    ///   at fault, in which case that text is left out.
+    ///
+    /// Since the recovered MIME type may not exist anywhere in the source, this returns a
+    /// [`MimeBuf`]. A MIME type that [`Mime::parse`] accepts is returned as it would be parsed,
+    /// but may still have problems reported, such as flag parameters.
+    ///
+    /// ## Errors
+    ///
+    /// Problems with the essence can't be recovered from, so an error is returned instead if
+    /// there is no slash, if the type or the subtype is missing, or if a name isn't a token.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mr_mime::{Mime, ParamsError, ParseError, constants};
+    ///
+    /// let (mime, problems) = Mime::parse_lossy("text / html; charset = utf-8; flowed").unwrap();
+    /// assert_eq!(mime.as_str(), "text/html;charset=utf-8;flowed");
+    /// assert_eq!(
+    ///     problems,
+    ///     [
+    ///         ParseError::StrayWhitespace,
+    ///         ParseError::StrayWhitespace,
+    ///         ParseError::InvalidParameter(ParamsError::MissingEquals),
+    ///     ]
+    /// );
+    ///
+    /// let (mime, problems) = Mime::parse_lossy("text/plain").unwrap();
+    /// assert_eq!(mime, constants::TEXT_PLAIN);
+    /// assert!(problems.is_empty());
+    ///
+    /// assert_eq!(Mime::parse_lossy("text").unwrap_err(), ParseError::NoSlash);
+    /// ```
+    pub fn parse_lossy(source: &str) -> Result<(MimeBuf, Vec<ParseError>), ParseError> {
+        let mut problems = Vec::new();
+
+        let (essence, mut rest) = match source.find(';') {
+            Some(semicolon) => (&source[..semicolon], &source[semicolon + 1..]),
+            None => (source, ""),
+        };
+        let slash = essence.find('/').ok_or(ParseError::NoSlash)?;
+        let (ty, subtype) = (&essence[..slash], &essence[slash + 1..]);
+        let (subtype, suffix) = match subtype.rfind('+') {
+            Some(plus) => (&subtype[..plus], Some(&subtype[plus + 1..])),
+            None => (subtype, None),
+        };
+
+        // `Mime::parse` allows whitespace before the essence and after the last name.
+        let mut stray_whitespace = ty.trim_end_matches(is_whitespace).len() != ty.len();
+        stray_whitespace |= subtype.trim_start_matches(is_whitespace).len() != subtype.len();
+        stray_whitespace |= suffix.is_some() && subtype.trim_end_matches(is_whitespace) != subtype;
+        stray_whitespace |= suffix.map_or(false, |suffix| {
+            suffix.trim_start_matches(is_whitespace).len() != suffix.len()
+        });
+        if stray_whitespace {
+            problems.push(ParseError::StrayWhitespace);
+        }
+
+        let ty = match ty.trim_matches(is_whitespace) {
+            "" => return Err(ParseError::MissingType),
+            ty => Type::new(ty).ok_or(ParseError::NonHttpCodepoints)?,
+        };
+        let subtype = match subtype.trim_matches(is_whitespace) {
+            "" => return Err(ParseError::MissingSubtype),
+            subtype => Subtype::new(subtype).ok_or(ParseError::NonHttpCodepoints)?,
+        };
+        let suffix = match suffix.map(|suffix| suffix.trim_matches(is_whitespace)) {
+            Some("") => {
+                problems.push(ParseError::InvalidToken);
+                None
+            }
+            Some(suffix) => Some(Suffix::new(suffix).ok_or(ParseError::NonHttpCodepoints)?),
+            None => None,
+        };
+
+        let mut parameters = Vec::new();
+        loop {
+            rest = rest.trim_start_matches(is_whitespace);
+            if rest.is_empty() {
+                break;
+            } else if let Some(after) = rest.strip_prefix(';') {
+                rest = after;
+                continue;
+            }
+
+            let key_end = rest.find(['=', ';']).unwrap_or(rest.len());
+            let key = rest[..key_end].trim_end_matches(is_whitespace);
+            let valid_key = is_token(key);
+            rest = &rest[key_end..];
+
+            let value = match rest.strip_prefix('=') {
+                Some(after) => after,
+                None => {
+                    if valid_key {
+                        parameters.push((key, &[][..], false));
+                    }
+                    problems.push(ParseError::InvalidParameter(ParamsError::MissingEquals));
+                    continue;
+                }
+            };
+            if key.len() != key_end || value.trim_start_matches(is_whitespace) != value {
+                problems.push(ParseError::StrayWhitespace);
+            }
+            let value = value.trim_start_matches(is_whitespace);
+
+            // Quoted values keep their escapes, like the ones `Mime::parse` finds.
+            let (value, escaped, error) = match value.strip_prefix('"') {
+                Some(quoted) => {
+                    let (mut value, after) = split_quoted(quoted);
+                    let error = if after.is_none() {
+                        // The value runs to the end of the source, so it may have swallowed
+                        // anything, such as a header that was smuggled in after a line break.
+                        if let Some(end) = value.bytes().position(|b| !is_http_quoted_codepoint(b))
+                        {
+                            value = split_quoted(&value[..end]).0;
+                        }
+                        Some(ParamsError::UnterminatedQuote)
+                    } else if !value.bytes().all(is_http_quoted_codepoint) {
+                        Some(ParamsError::InvalidValue)
+                    } else {
+                        None
+                    };
+                    let after = after.unwrap_or("");
+                    rest = after;
+
+                    // Anything between the closing quote and the next semicolon is dropped.
+                    let after = after.trim_start_matches(is_whitespace);
+                    if !after.is_empty() && !after.starts_with(';') {
+                        problems.push(ParseError::InvalidParameter(ParamsError::InvalidValue));
+                        rest = after.find(';').map_or("", |semicolon| &after[semicolon..]);
+                    }
+
+                    (value, true, error)
+                }
+                None => {
+                    let end = value.find(';').unwrap_or(value.len());
+                    let raw = &value[..end];
+                    rest = &value[end..];
+
+                    let value = raw.trim_end_matches(is_whitespace);
+                    if value.len() != raw.len() {
+                        problems.push(ParseError::StrayWhitespace);
+                    }
+
+                    let error = if value.is_empty() {
+                        Some(ParamsError::EmptyValue)
+                    } else if value.contains('=') {
+                        Some(ParamsError::MultipleEquals)
+                    } else if !is_token(value) {
+                        Some(ParamsError::InvalidValue)
+                    } else {
+                        None
+                    };
+
+                    (value, false, error)
+                }
+            };
+
+            if let Some(error) = error {
+                problems.push(ParseError::InvalidParameter(error));
+            }
+            if !valid_key {
+                problems.push(ParseError::InvalidParameter(ParamsError::InvalidKey));
+            } else if error.map_or(true, |error| error == ParamsError::UnterminatedQuote) {
+                parameters.push((key, value.as_bytes(), escaped));
+            }
+        }
+
+        let mime = MimeBuf::from_parts(ty, subtype, suffix, parameters.iter().copied());
+        Ok((mime, problems))
+    }
+}
+
+/// Check that a parameter given as plain strings can be written out, reporting it at `index`.
+fn check_parameter(key: &str, value: &str, index: usize) -> Result<(), InvalidMime> {
+    if !is_token(key) {
+        Err(InvalidMime::ParameterKey(index))
+    } else if !value.bytes().all(is_http_quoted_codepoint) {
+        Err(InvalidMime::ParameterValue(index))
+    } else {
+        Ok(())
+    }
+}
+
+/// Is this whitespace that `Mime::parse_lossy` strips?
+fn is_whitespace(c: char) -> bool {
+    c.is_ascii() && is_http_whitespace(c as u8)
+}
+
+/// Split a quoted string, without its opening quote, into its value and what follows it.
+///
+/// Escapes are kept in the value. If the string is never terminated, nothing follows it, and
+/// the value is the rest of the string without a trailing backslash, which would escape the
+/// quote that it gets written out with.
+fn split_quoted(quoted: &str) -> (&str, Option<&str>) {
+    let mut escaped = false;
+    for (i, c) in quoted.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return (&quoted[..i], Some(&quoted[i + 1..])),
+            _ => {}
+        }
+    }
+
+    match escaped {
+        true => (&quoted[..quoted.len() - 1], None),
+        false => (quoted, None),
+    }
+}
+
+#[cfg(all(test, feature = "tables"))]
+mod mime_buf_test {
+    use super::*;
+    use crate::constants;
+
+    use alloc::string::ToString;
+
+    #[test]
+    fn mime_buf_from_mime() {
+        let mime = Mime::parse("image/x-custom+xml; a=b; c=\"d e\"").unwrap();
+        let owned = MimeBuf::from(mime);
+
+        assert_eq!(owned, mime);
+        assert_eq!(owned.as_str(), "image/x-custom+xml;a=b;c=\"d e\"");
+        assert_eq!(owned.as_mime().r#type(), constants::types::IMAGE);
+        assert_eq!(owned.as_mime().subtype(), "x-custom");
+        assert_eq!(owned.as_mime().suffix(), Some(constants::suffixes::XML));
+    }
+
+    #[test]
+    fn mime_buf_outlives_the_parsed_buffer() {
+        let source = String::from("Image/SVG+xml; Charset=\"utf-8\"; level=1");
+        let owned = Mime::parse(&source).unwrap().to_owned_mime();
+        drop(source);
+
+        assert_eq!(owned.r#type(), constants::types::IMAGE);
+        assert_eq!(owned.subtype(), constants::subtypes::SVG);
+        assert_eq!(owned.suffix(), Some(constants::suffixes::XML));
+        assert_eq!(owned.essence(), Mime::parse("image/svg").unwrap());
+        assert_eq!(
+            owned.as_mime().essence_with_suffix(),
+            constants::IMAGE_SVG_XML
+        );
+        assert_eq!(owned.get_parameter("charset"), Some(b"utf-8".as_ref()));
+        assert_eq!(owned.get_parameter("format"), None);
+        assert!(owned
+            .parameters()
+            .eq([("Charset", b"utf-8".as_ref()), ("level", b"1".as_ref())]
+                .iter()
+                .copied()));
+
+        assert_eq!(
+            owned,
+            Mime::parse("image/svg+xml; level=1; charset=utf-8").unwrap()
+        );
+        assert_ne!(owned, constants::IMAGE_SVG_XML);
+        assert_eq!(owned.to_string(), "image/svg+xml;Charset=utf-8;level=1");
+
+        let bare = constants::TEXT_PLAIN.to_owned_mime();
+        assert_eq!(bare, constants::TEXT_PLAIN);
+        assert_eq!(bare.parameters().count(), 0);
+    }
+
+    #[test]
+    fn mime_to_ascii_lowercase() {
+        let mime = Mime::parse(
+            "Image/X-Custom+XML; CharSet=\"UTF-8\"; Boundary=AbC; NAME=\"My File.TXT\"",
+        )
+        .unwrap();
+        let lower = mime.to_ascii_lowercase();
+
+        assert_eq!(
+            lower.as_str(),
+            "image/x-custom+xml;charset=utf-8;boundary=AbC;name=\"My File.TXT\""
+        );
+        assert_eq!(lower.essence(), mime.essence());
+        assert_eq!(lower.r#type(), constants::types::IMAGE);
+        assert!(matches!(lower.r#type().0, Name::Interned(_)));
+        assert!(matches!(lower.subtype().0, Name::Dynamic("x-custom")));
+        assert_eq!(lower.suffix(), Some(constants::suffixes::XML));
+
+        let bare = Mime::parse("TEXT/Plain").unwrap().to_ascii_lowercase();
+        assert_eq!(bare.as_str(), "text/plain");
+        assert_eq!(bare.parameters().count(), 0);
+    }
+
+    #[test]
+    fn mime_to_ascii_lowercase_keeps_escapes() {
+        let source = r#"Text/Plain; A="x\"y"; B="C:\\"; c=d"#;
+        let lower = Mime::parse(source).unwrap().to_ascii_lowercase();
+        assert_eq!(lower.as_str(), r#"text/plain;a="x\"y";b="C:\\";c=d"#);
+
+        let parsed = Mime::parse(lower.as_str()).unwrap();
+        assert_eq!(parsed.parameters().count(), 3);
+        assert_eq!(parsed.get_parameter("a"), Some(&br#"x\"y"#[..]));
+        assert_eq!(parsed.get_parameter("c"), Some(&b"d"[..]));
+        assert_eq!(
+            lower.as_mime().to_ascii_lowercase().as_str(),
+            lower.as_str()
+        );
+    }
+
+    #[test]
+    fn mime_buf_parse_keeps_an_owned_string() {
+        let source = String::from("application/json; charset=utf-8");
+        let (pointer, capacity) = (source.as_ptr(), source.capacity());
+
+        let owned = MimeBuf::parse(source).unwrap();
+        assert_eq!(owned.as_str().as_ptr(), pointer);
+        assert_eq!(String::from(owned.clone()).capacity(), capacity);
+        assert_eq!(
+            owned,
+            Mime::parse("application/json; charset=utf-8").unwrap()
+        );
+        assert_eq!(owned.as_str(), "application/json; charset=utf-8");
+
+        let parsed: MimeBuf = "Image/SVG+xml; a=\"b c\"".parse().unwrap();
+        assert_eq!(parsed, Mime::parse("image/svg+xml; a=\"b c\"").unwrap());
+        assert_eq!(parsed.as_str(), "Image/SVG+xml; a=\"b c\"");
+        assert_eq!(parsed.suffix(), Some(constants::suffixes::XML));
+
+        assert_eq!("text".parse::<MimeBuf>(), Err(ParseError::NoSlash));
+        assert_eq!(MimeBuf::parse("/plain"), Err(ParseError::MissingType));
+    }
+
+    #[test]
+    fn mime_buf_displays_like_mime() {
+        for source in &[
+            "text/html; charset=utf-8",
+            "Image/SVG+xml; a=\"b c\"; b=\"token\"",
+            "text/plain;flag",
+        ] {
+            let parsed = MimeBuf::parse(*source).unwrap();
+            let converted = MimeBuf::from(Mime::parse(source).unwrap());
+            assert_eq!(parsed, converted);
+            assert_eq!(parsed.to_string(), converted.to_string());
+            assert_eq!(parsed.to_string(), parsed.as_mime().to_string());
+            assert_eq!(parsed.as_str(), *source);
+        }
+
+        let parsed = MimeBuf::parse("text/html; charset=utf-8").unwrap();
+        assert_eq!(
+            alloc::format!("{:>25}", parsed),
+            "  text/html;charset=utf-8"
+        );
+    }
+
+    #[test]
+    fn mime_buf_converts_to_and_from_string() {
+        let mime = Mime::parse("image/x-custom+xml; charset=utf-8").unwrap();
+        let string = String::from(mime);
+        assert_eq!(string, "image/x-custom+xml;charset=utf-8");
+        assert_eq!(String::from(constants::TEXT_PLAIN), "text/plain");
+
+        let owned = MimeBuf::try_from(string.clone()).unwrap();
+        assert_eq!(owned, mime);
+        assert_eq!(String::from(owned), string);
+
+        let quoted = Mime::parse(r#"text/plain; a="b c"; d="e\"f\\""#).unwrap();
+        let string = String::from(quoted);
+        assert_eq!(string, r#"text/plain;a="b c";d="e\"f\\""#);
+        let parsed = Mime::parse(&string).unwrap();
+        assert_eq!(parsed, quoted);
+        assert_eq!(parsed.get_parameter("a"), Some(&b"b c"[..]));
+
+        let string = String::from("image/x-custom+xml; charset=utf-8; a=\"b c\"");
+        let owned = MimeBuf::try_from(string.clone()).unwrap();
+        assert_eq!(owned.as_mime().subtype(), "x-custom");
+        assert_eq!(owned.as_mime().suffix(), Some(constants::suffixes::XML));
+        assert_eq!(owned.as_mime().get_parameter("a"), Some(b"b c".as_ref()));
+        assert_eq!(String::from(owned), string);
+
+        let owned = MimeBuf::try_from(String::from("  Text/Plain ;charset=utf-8")).unwrap();
+        assert_eq!(owned.as_mime().essence(), constants::TEXT_PLAIN);
+        assert_eq!(owned.as_mime().r#type(), constants::types::TEXT);
+        assert_eq!(owned.as_str(), "  Text/Plain ;charset=utf-8");
+
+        assert_eq!(
+            MimeBuf::try_from(String::from("text")),
+            Err(ParseError::NoSlash)
+        );
+    }
+
+    #[test]
+    fn mime_overwrite_parameter_replaces_first_match() {
+        let mime = Mime::parse("text/plain; a=1; Charset=us-ascii; charset=latin1").unwrap();
+
+        let owned = mime.overwrite_parameter("CHARSET", "utf-8").unwrap();
+        assert_eq!(
+            owned.as_str(),
+            "text/plain;a=1;Charset=utf-8;charset=latin1"
+        );
+        assert_eq!(owned.as_mime().parameters().count(), 3);
+
+        let owned = mime.overwrite_parameter("format", "flowed").unwrap();
+        assert_eq!(
+            owned.as_str(),
+            "text/plain;a=1;Charset=us-ascii;charset=latin1;format=flowed"
+        );
+
+        let owned = constants::TEXT_HTML
+            .overwrite_parameter("charset", "utf-8")
+            .unwrap();
+        assert_eq!(owned.as_str(), "text/html;charset=utf-8");
+    }
+
+    #[test]
+    fn mime_overwrite_parameter_checks_parameter() {
+        let mime = Mime::parse("text/plain; a=1; title=\"x\\\"y\"").unwrap();
+
+        let owned = mime.overwrite_parameter("a", r#"say "hi""#).unwrap();
+        assert_eq!(owned.as_str(), r#"text/plain;a="say \"hi\"";title="x\"y""#);
+
+        assert_eq!(
+            mime.overwrite_parameter("bad key", "v"),
+            Err(InvalidMime::ParameterKey(2))
+        );
+        assert_eq!(
+            mime.overwrite_parameter("title", "a\r\nSet-Cookie: b"),
+            Err(InvalidMime::ParameterValue(1))
+        );
+        assert_eq!(
+            mime.overwrite_parameter("a", "\0"),
+            Err(InvalidMime::ParameterValue(0))
+        );
+    }
+
+    #[test]
+    fn mime_with_parameter_adds_and_replaces() {
+        let mime = Mime::parse("text/html; level=1; Charset=us-ascii").unwrap();
+
+        let added = mime.with_parameter("format", "flowed").unwrap();
+        assert_eq!(
+            added.as_str(),
+            "text/html;level=1;Charset=us-ascii;format=flowed"
+        );
+
+        let replaced = mime.with_parameter("charset", "utf-8").unwrap();
+        assert_eq!(replaced.as_str(), "text/html;level=1;Charset=utf-8");
+
+        let params: &[(&str, &[u8])] = &[("level", b"1"), ("Charset", b"utf-8")];
+        let expected = Mime::new(
+            constants::types::TEXT,
+            constants::subtypes::HTML,
+            None,
+            params,
+        );
+        assert_eq!(replaced, expected);
+        assert_eq!(MimeBuf::try_from(replaced.to_string()).unwrap(), expected);
+
+        assert_eq!(
+            mime.with_parameter("bad key", "x"),
+            Err(InvalidMime::ParameterKey(2))
+        );
+        assert_eq!(
+            mime.with_parameter("CHARSET", "\0"),
+            Err(InvalidMime::ParameterValue(1))
+        );
+        assert_eq!(
+            mime.with_parameter("level", "1\r\nSet-Cookie: a=b"),
+            Err(InvalidMime::ParameterValue(0))
+        );
+    }
+
+    #[test]
+    fn mime_with_parameter_escapes_quotes_and_backslashes() {
+        let mime = Mime::parse("text/plain; charset=utf-8").unwrap();
+
+        for &(value, written) in &[
+            (r#"a"b"#, r#"text/plain;charset=utf-8;name="a\"b""#),
+            (r"a\", r#"text/plain;charset=utf-8;name="a\\""#),
+            (r#"\"\"#, r#"text/plain;charset=utf-8;name="\\\"\\""#),
+        ] {
+            let owned = mime.with_parameter("name", value).unwrap();
+            assert_eq!(owned.as_str(), written);
+
+            // The value survives being parsed again, and so does everything after it.
+            let parsed = Mime::parse(owned.as_str()).unwrap();
+            assert_eq!(parsed.parameters().count(), 2);
+            let (_, stored, escaped) = parsed.escaped_parameters().nth(1).unwrap();
+            let stored = core::str::from_utf8(stored).unwrap();
+            assert_eq!(crate::unescape(stored, escaped).collect::<String>(), value);
+
+            let owned = owned.as_mime().with_parameter("format", "flowed").unwrap();
+            assert_eq!(
+                owned.as_mime().get_parameter("format"),
+                Some(&b"flowed"[..])
+            );
+        }
+    }
+
+    #[test]
+    fn mime_without_parameter_removes_every_match() {
+        let mime = Mime::parse("text/plain; a=1; charset=utf-8; b=2; CHARSET=latin1").unwrap();
+
+        let removed = mime.without_parameter("Charset");
+        assert_eq!(removed.as_str(), "text/plain;a=1;b=2");
+        assert_eq!(removed, Mime::parse("text/plain; a=1; b=2").unwrap());
+
+        let unchanged = mime.without_parameter("format");
+        assert_eq!(unchanged, mime);
+        assert_eq!(unchanged.as_mime().parameters().count(), 4);
+
+        let bare = constants::TEXT_PLAIN.without_parameter("charset");
+        assert_eq!(bare.as_str(), "text/plain");
+    }
+
+    #[test]
+    fn mime_merge_params_overrides_existing_keys() {
+        let base = Mime::parse("text/html; Charset=us-ascii; level=1").unwrap();
+        let merged = base
+            .merge_params(&[("charset", "utf-8"), ("lang", "en"), ("x", "a b")])
+            .unwrap();
+
+        assert_eq!(
+            merged.as_str(),
+            "text/html;Charset=utf-8;level=1;lang=en;x=\"a b\""
+        );
+        assert_eq!(
+            merged.as_mime().get_parameter("charset"),
+            Some(b"utf-8".as_ref())
+        );
+        assert_eq!(merged.as_mime().parameters().count(), 4);
+
+        let merged = constants::TEXT_PLAIN
+            .merge_params(&[("a", "1"), ("b", "2"), ("A", "3")])
+            .unwrap();
+        assert_eq!(merged.as_str(), "text/plain;b=2;A=3");
+
+        let merged = base.merge_params(&[]).unwrap();
+        assert_eq!(merged, base);
+    }
+
+    #[test]
+    fn mime_merge_params_checks_parameters() {
+        let base = Mime::parse("text/plain; charset=us-ascii").unwrap();
+
+        let merged = base
+            .merge_params(&[("title", r"a\b"), ("name", "\"")])
+            .unwrap();
+        assert_eq!(
+            merged.as_str(),
+            r#"text/plain;charset=us-ascii;title="a\\b";name="\"""#
+        );
+
+        for &(extra, error) in &[
+            (&[("bad key", "v")][..], InvalidMime::ParameterKey(0)),
+            (&[("a", "1"), ("", "2")][..], InvalidMime::ParameterKey(1)),
+            (
+                &[("a", "1"), ("b;c", "2")][..],
+                InvalidMime::ParameterKey(1),
+            ),
+            (&[("charset", "a\r\nb")][..], InvalidMime::ParameterValue(0)),
+            (
+                &[("a", "1"), ("b", "\x7f")][..],
+                InvalidMime::ParameterValue(1),
+            ),
+        ] {
+            assert_eq!(base.merge_params(extra), Err(error), "{:?}", extra);
+        }
+    }
+
+    #[test]
+    fn mime_parse_from_iterator_builds_mime() {
+        let parts = ["text", "plain", "charset", "utf-8", "title", "a b"];
+        let owned = Mime::parse_from_iterator(parts.iter().copied()).unwrap();
+        assert_eq!(owned.as_str(), "text/plain;charset=utf-8;title=\"a b\"");
+        assert_eq!(owned.as_mime().essence(), constants::TEXT_PLAIN);
+
+        let parts = ["application", "x-thing+json", "flag"];
+        let owned = Mime::parse_from_iterator(parts.iter().copied()).unwrap();
+        assert_eq!(owned.as_str(), "application/x-thing+json;flag");
+        assert_eq!(owned.as_mime().suffix(), Some(constants::suffixes::JSON));
+
+        let owned = Mime::parse_from_iterator(["*", "*"].iter().copied()).unwrap();
+        assert!(owned.as_mime().is_wildcard());
+
+        let parts = [
+            "text",
+            "plain",
+            "title",
+            r#"say "hi""#,
+            "path",
+            r"C:\",
+            "a",
+            "b",
+        ];
+        let owned = Mime::parse_from_iterator(parts.iter().copied()).unwrap();
+        assert_eq!(
+            owned.as_str(),
+            r#"text/plain;title="say \"hi\"";path="C:\\";a=b"#
+        );
+        let parsed = Mime::parse(owned.as_str()).unwrap();
+        assert_eq!(parsed.parameters().count(), 3);
+        assert_eq!(parsed.get_parameter("a"), Some(&b"b"[..]));
+    }
+
+    #[test]
+    fn mime_parse_from_iterator_rejects_bad_parts() {
+        let cases: &[(&[&str], ParseError)] = &[
+            (&[], ParseError::MissingType),
+            (&["", "plain"], ParseError::MissingType),
+            (&["text"], ParseError::MissingSubtype),
+            (&["text", ""], ParseError::MissingSubtype),
+            (&["text", "+xml"], ParseError::MissingSubtype),
+            (&["te xt", "plain"], ParseError::NonHttpCodepoints),
+            (&["text", "pl/ain"], ParseError::NonHttpCodepoints),
+            (&["text", "plain+"], ParseError::NonHttpCodepoints),
+            (
+                &["text", "plain", "a;b", "c"],
+                ParseError::NonHttpCodepoints,
+            ),
+            (
+                &["text", "plain", "a", "b\r\nc"],
+                ParseError::NonHttpCodepoints,
+            ),
+        ];
+
+        for &(parts, error) in cases {
+            assert_eq!(
+                Mime::parse_from_iterator(parts.iter().copied()),
+                Err(error),
+                "{:?}",
+                parts
+            );
+        }
+    }
+
+    #[test]
+    fn mime_with_param_adds_parameter() {
+        let mime = Mime::parse("multipart/form-data").unwrap();
+        let owned = mime.with_param("boundary", "a b").unwrap();
+
+        assert_eq!(owned.as_str(), "multipart/form-data;boundary=\"a b\"");
+        assert_eq!(
+            owned.as_mime().get_parameter("boundary"),
+            Some(b"a b".as_ref())
+        );
+        assert_eq!(owned.as_mime().essence(), constants::MULTIPART_FORM_DATA);
+    }
+
+    #[test]
+    fn mime_with_param_replaces_parameter() {
+        let mime = Mime::parse("text/plain; Charset=us-ascii; format=flowed").unwrap();
+        let owned = mime.with_param("charset", "utf-8").unwrap();
+
+        assert_eq!(owned.to_string(), "text/plain;Charset=utf-8;format=flowed");
+        assert_eq!(owned.as_mime().parameters().count(), 2);
+        assert_eq!(
+            owned,
+            Mime::parse("text/plain; charset=utf-8; format=flowed").unwrap()
+        );
+    }
+
+    #[test]
+    fn mime_with_param_escapes_and_rejects_values() {
+        let mime = constants::TEXT_PLAIN;
+
+        for &(value, written) in &[
+            (r#"a"b"#, r#"text/plain;name="a\"b""#),
+            (r"a\", r#"text/plain;name="a\\""#),
+            (r#"\""#, r#"text/plain;name="\\\"""#),
+            ("caf\u{e9}", "text/plain;name=\"caf\u{e9}\""),
+        ] {
+            let owned = mime.with_param("name", value).unwrap();
+            assert_eq!(owned.as_str(), written);
+
+            let parsed = Mime::parse(owned.as_str()).unwrap();
+            let (_, stored, escaped) = parsed.escaped_parameters().next().unwrap();
+            let stored = core::str::from_utf8(stored).unwrap();
+            assert_eq!(crate::unescape(stored, escaped).collect::<String>(), value);
+        }
+
+        assert_eq!(
+            mime.with_param("name", "a\r\nSet-Cookie: b"),
+            Err(InvalidMime::ParameterValue(0))
+        );
+        assert_eq!(
+            mime.with_param("name", "a\0"),
+            Err(InvalidMime::ParameterValue(0))
+        );
+        assert_eq!(
+            mime.with_param("bad key", "a"),
+            Err(InvalidMime::ParameterKey(0))
+        );
+    }
+
+    #[test]
+    fn mime_with_parameters_from_map() {
+        let mime = Mime::parse("image/svg+xml; charset=us-ascii; level=1").unwrap();
+
+        // Borrowed pairs, with a duplicate key.
+        let owned = mime
+            .with_parameters_from_map([
+                ("b", "x y"),
+                ("A", "1"),
+                ("B", "2"),
+                ("a", "3"),
+                ("c", "4"),
+            ])
+            .unwrap();
+        assert_eq!(owned.as_str(), "image/svg+xml;b=2;A=3;c=4");
+        assert_eq!(
+            owned.as_mime().essence_with_suffix(),
+            constants::IMAGE_SVG_XML
+        );
+
+        // Owned pairs from a map.
+        let mut map = alloc::collections::BTreeMap::new();
+        map.insert(String::from("name"), String::from("report.pdf"));
+        map.insert(String::from("boundary"), String::from("a b"));
+        let owned = constants::MULTIPART_FORM_DATA
+            .with_parameters_from_map(&map)
+            .unwrap();
+        assert_eq!(
+            owned.as_str(),
+            "multipart/form-data;boundary=\"a b\";name=report.pdf"
+        );
+
+        let owned = mime
+            .with_parameters_from_map(core::iter::empty::<(&str, &str)>())
+            .unwrap();
+        assert_eq!(owned, constants::IMAGE_SVG_XML);
+    }
+
+    #[test]
+    fn mime_with_parameters_from_map_checks_every_pair() {
+        let mime = constants::TEXT_PLAIN;
+
+        let owned = mime
+            .with_parameters_from_map([("name", r#"a "b""#), ("path", r"c:\")])
+            .unwrap();
+        assert_eq!(owned.as_str(), r#"text/plain;name="a \"b\"";path="c:\\""#);
+        assert_eq!(Mime::parse(owned.as_str()).unwrap().parameters().count(), 2);
+
+        for &(map, error) in &[
+            (&[("a b", "1")][..], InvalidMime::ParameterKey(0)),
+            (&[("a", "1"), ("", "2")][..], InvalidMime::ParameterKey(1)),
+            (
+                &[("a", "1\r\nSet-Cookie: b")][..],
+                InvalidMime::ParameterValue(0),
+            ),
+            // Values that are replaced later are still checked.
+            (
+                &[("a", "\0"), ("A", "1")][..],
+                InvalidMime::ParameterValue(0),
+            ),
+        ] {
+            assert_eq!(
+                mime.with_parameters_from_map(map.iter().copied()),
+                Err(error),
+                "{:?}",
+                map
+            );
+        }
+    }
+
+    #[test]
+    fn mime_parse_lossy_accepts_valid_types_quietly() {
+        for source in &[
+            "text/plain",
+            " image/svg+xml ",
+            "text/html;charset=utf-8",
+            "text/html; charset=utf-8;; format=flowed",
+            "multipart/form-data; boundary=\"a; \\\"b\\\"\"",
+        ] {
+            let (mime, problems) = Mime::parse_lossy(source).unwrap();
+            assert_eq!(mime, Mime::parse(source).unwrap(), "{}", source);
+            assert_eq!(problems, [], "{}", source);
+        }
+    }
+
+    #[test]
+    fn mime_parse_lossy_accumulates_problems() {
+        use ParamsError::*;
+        use ParseError::{InvalidParameter, InvalidToken, StrayWhitespace};
+
+        let (mime, problems) = Mime::parse_lossy(
+            "application /vnd.a+ ; a = b ; flag; c=\"d\" e; f=; g=h=i; j k=l; m=\"n\\",
+        )
+        .unwrap();
+        assert_eq!(mime.as_str(), "application/vnd.a;a=b;flag;c=d;m=n");
+        assert_eq!(
+            problems,
+            [
+                StrayWhitespace,
+                InvalidToken,
+                StrayWhitespace,
+                StrayWhitespace,
+                InvalidParameter(MissingEquals),
+                InvalidParameter(InvalidValue),
+                InvalidParameter(EmptyValue),
+                InvalidParameter(MultipleEquals),
+                InvalidParameter(InvalidKey),
+                InvalidParameter(UnterminatedQuote),
+            ]
+        );
+    }
+
+    #[test]
+    fn mime_parse_lossy_recovers_quoted_strings() {
+        let (mime, problems) = Mime::parse_lossy("text/plain; a=\"b; c=d").unwrap();
+        assert_eq!(mime.get_parameter("a"), Some(&b"b; c=d"[..]));
+        assert_eq!(mime.get_parameter("c"), None);
+        assert_eq!(
+            problems,
+            [ParseError::InvalidParameter(ParamsError::UnterminatedQuote)]
+        );
+
+        for &(source, value) in &[
+            ("text/plain; a=\"x\r\nSet-Cookie: y", &b"x"[..]),
+            ("text/plain; a=\"x\\\"y\0z", &b"x\\\"y"[..]),
+            ("text/plain; a=\"x\\\n", &b"x"[..]),
+            ("text/plain; a=\"\n", &b""[..]),
+        ] {
+            let (mime, problems) = Mime::parse_lossy(source).unwrap();
+            assert_eq!(mime.get_parameter("a"), Some(value), "{:?}", source);
+            assert!(!mime.as_str().contains(|c: char| c.is_ascii_control()));
+            assert_eq!(Mime::parse(mime.as_str()).unwrap(), mime);
+            assert_eq!(
+                problems,
+                [ParseError::InvalidParameter(ParamsError::UnterminatedQuote)]
+            );
+        }
+
+        let (mime, problems) = Mime::parse_lossy("text/plain; a=\"b\\\"\" junk; c=d").unwrap();
+        assert_eq!(mime.get_parameter("a"), Some(&b"b\\\""[..]));
+        assert_eq!(mime.get_parameter("c"), Some(&b"d"[..]));
+        assert_eq!(
+            problems,
+            [ParseError::InvalidParameter(ParamsError::InvalidValue)]
+        );
+    }
+
+    #[test]
+    fn mime_parse_lossy_rejects_broken_essences() {
+        for &(source, err) in &[
+            ("", ParseError::NoSlash),
+            ("text", ParseError::NoSlash),
+            ("text;a=/b", ParseError::NoSlash),
+            (" /html", ParseError::MissingType),
+            ("text/ ", ParseError::MissingSubtype),
+            ("text/+xml", ParseError::MissingSubtype),
+            ("te xt/html", ParseError::NonHttpCodepoints),
+            ("text/html+x y", ParseError::NonHttpCodepoints),
+        ] {
+            assert_eq!(Mime::parse_lossy(source).unwrap_err(), err, "{:?}", source);
+        }
+    }
+}
@@ -0,0 +1,245 @@
+//! A builder for owned MIME types.
+
+use crate::{is_http_quoted_codepoint, is_token, InvalidMime, MimeBuf, Subtype, Suffix, Type};
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// A builder for a [`MimeBuf`] that is assembled piece by piece.
+///
+/// Unlike [`Mime::new`](crate::Mime::new), the builder owns its components, so there is no need
+/// to keep a slice of parameters alive. Nothing is checked until [`MimeBuilder::build`] is
+/// called, which leaves the builder untouched so that it can be reused.
+///
+/// ## Example
+///
+/// ```rust
+/// use mr_mime::{Mime, MimeBuilder};
+///
+/// let mut builder = MimeBuilder::new();
+/// builder.type_("multipart").subtype("form-data").boundary("abc123");
+///
+/// let form = builder.build().unwrap();
+/// assert_eq!(form, Mime::parse("multipart/form-data; boundary=abc123").unwrap());
+///
+/// let form = builder.boundary("xyz789").build().unwrap();
+/// assert_eq!(form.to_string(), "multipart/form-data;boundary=xyz789");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct MimeBuilder {
+    /// The type of the MIME type.
+    ty: String,
+
+    /// The subtype of the MIME type.
+    subtype: String,
+
+    /// The suffix of the MIME type, if any.
+    suffix: Option<String>,
+
+    /// The parameters of the MIME type, in the order they were first set.
+    parameters: Vec<(String, String)>,
+}
+
+impl MimeBuilder {
+    /// Create a new, empty builder.
+    ///
+    /// The type and subtype must be set before building.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the type, like `text` or `image`.
+    pub fn type_(&mut self, ty: &str) -> &mut Self {
+        self.ty = ty.to_string();
+        self
+    }
+
+    /// Set the subtype, like `plain` or `svg`.
+    ///
+    /// The suffix is set separately with [`MimeBuilder::suffix`].
+    pub fn subtype(&mut self, subtype: &str) -> &mut Self {
+        self.subtype = subtype.to_string();
+        self
+    }
+
+    /// Set the suffix, like `xml` in `image/svg+xml`.
+    pub fn suffix(&mut self, suffix: &str) -> &mut Self {
+        self.suffix = Some(suffix.to_string());
+        self
+    }
+
+    /// Set a parameter.
+    ///
+    /// If a parameter with the same key (compared case-insensitively) is already set, its value
+    /// is replaced. Otherwise, the parameter is added to the end. Values that aren't tokens are
+    /// written out as quoted strings.
+    pub fn param(&mut self, key: &str, value: &str) -> &mut Self {
+        match self
+            .parameters
+            .iter_mut()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+        {
+            Some((_, v)) => *v = value.to_string(),
+            None => self.parameters.push((key.to_string(), value.to_string())),
+        }
+
+        self
+    }
+
+    /// Set the `charset` parameter.
+    pub fn charset(&mut self, charset: &str) -> &mut Self {
+        self.param("charset", charset)
+    }
+
+    /// Set the `boundary` parameter.
+    pub fn boundary(&mut self, boundary: &str) -> &mut Self {
+        self.param("boundary", boundary)
+    }
+
+    /// Build the MIME type.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an [`InvalidMime`] if the type, subtype or suffix is empty or isn't a token, if a
+    /// parameter key isn't a token, or if a parameter value contains characters that can't be
+    /// quoted. Parameters are indexed in the order they were first set.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mr_mime::{InvalidMime, MimeBuilder};
+    ///
+    /// let html = MimeBuilder::new()
+    ///     .type_("text")
+    ///     .subtype("html")
+    ///     .charset("utf-8")
+    ///     .build();
+    /// assert_eq!(html.unwrap().to_string(), "text/html;charset=utf-8");
+    ///
+    /// let bad = MimeBuilder::new()
+    ///     .type_("text")
+    ///     .subtype("plain")
+    ///     .param("bad key", "value")
+    ///     .build();
+    /// assert_eq!(bad, Err(InvalidMime::ParameterKey(0)));
+    /// ```
+    pub fn build(&self) -> Result<MimeBuf, InvalidMime> {
+        let ty = Some(self.ty.as_str())
+            .filter(|ty| is_token(ty))
+            .and_then(Type::new)
+            .ok_or(InvalidMime::Type)?;
+        let subtype = Some(self.subtype.as_str())
+            .filter(|subtype| is_token(subtype))
+            .and_then(Subtype::new)
+            .ok_or(InvalidMime::Subtype)?;
+        let suffix = match &self.suffix {
+            Some(suffix) if is_token(suffix) => {
+                Some(Suffix::new(suffix).ok_or(InvalidMime::Suffix)?)
+            }
+            Some(_) => return Err(InvalidMime::Suffix),
+            None => None,
+        };
+
+        let mut parameters = Vec::with_capacity(self.parameters.len());
+        for (i, (key, value)) in self.parameters.iter().enumerate() {
+            if !is_token(key) {
+                return Err(InvalidMime::ParameterKey(i));
+            } else if !value.bytes().all(is_http_quoted_codepoint) {
+                return Err(InvalidMime::ParameterValue(i));
+            }
+
+            parameters.push((key.as_str(), value.as_bytes(), false));
+        }
+
+        Ok(MimeBuf::from_parts(
+            ty,
+            subtype,
+            suffix,
+            parameters.into_iter(),
+        ))
+    }
+}
+
+#[cfg(all(test, feature = "tables"))]
+mod builder_test {
+    use super::*;
+    use crate::{constants, Mime};
+
+    #[test]
+    fn builds_multipart_form_data_with_boundary() {
+        let form = MimeBuilder::new()
+            .type_("multipart")
+            .subtype("form-data")
+            .boundary("----WebKitFormBoundary7MA4YWxkTrZu0gW")
+            .build()
+            .unwrap();
+
+        assert_eq!(form.as_mime().essence(), constants::MULTIPART_FORM_DATA);
+        assert_eq!(
+            form.as_mime().get_parameter("boundary"),
+            Some(b"----WebKitFormBoundary7MA4YWxkTrZu0gW".as_ref())
+        );
+    }
+
+    #[test]
+    fn builds_text_html_with_charset() {
+        let mut builder = MimeBuilder::new();
+        builder.type_("text").subtype("html").charset("us-ascii");
+
+        let html = builder.charset("utf-8").build().unwrap();
+        assert_eq!(html, Mime::parse("text/html; charset=utf-8").unwrap());
+        assert_eq!(html.to_string(), "text/html;charset=utf-8");
+
+        // The builder can be reused, and keys are compared case-insensitively.
+        let html = builder.param("Charset", "latin1").build().unwrap();
+        assert_eq!(html.to_string(), "text/html;charset=latin1");
+    }
+
+    #[test]
+    fn builds_suffix_and_quoted_values() {
+        let svg = MimeBuilder::new()
+            .type_("image")
+            .subtype("svg")
+            .suffix("xml")
+            .param("title", "a \"b\" c")
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            svg.as_mime().essence_with_suffix(),
+            constants::IMAGE_SVG_XML
+        );
+        assert_eq!(svg.as_str(), r#"image/svg+xml;title="a \"b\" c""#);
+        assert_eq!(
+            svg,
+            Mime::parse(r#"image/svg+xml; title="a \"b\" c""#).unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_components() {
+        let mut builder = MimeBuilder::new();
+        assert_eq!(builder.build(), Err(InvalidMime::Type));
+
+        builder.type_("text");
+        assert_eq!(builder.build(), Err(InvalidMime::Subtype));
+
+        builder.subtype("plain").suffix("");
+        assert_eq!(builder.build(), Err(InvalidMime::Suffix));
+
+        let mut builder = MimeBuilder::new();
+        builder
+            .type_("text")
+            .subtype("plain")
+            .charset("utf-8")
+            .param("bad key", "value");
+        assert_eq!(builder.build(), Err(InvalidMime::ParameterKey(1)));
+
+        let mut builder = MimeBuilder::new();
+        builder
+            .type_("text")
+            .subtype("plain")
+            .param("x", "line\r\nbreak");
+        assert_eq!(builder.build(), Err(InvalidMime::ParameterValue(0)));
+    }
+}
@@ -0,0 +1,149 @@
+//! A typed `Content-Type` header for the `headers` crate.
+
+use crate::{Mime, MimeBuf};
+
+use core::fmt;
+
+use headers::{Error, Header, HeaderName, HeaderValue};
+
+/// The `Content-Type` header, as a typed header for the [`headers`] crate.
+///
+/// Decoding parses the first value of the header, and ignores any others, the same way that
+/// `headers::ContentType` does. If the header is missing, [`headers::HeaderMapExt::typed_get`]
+/// returns `None` without decoding anything. Encoding writes the MIME type back out as it was
+/// parsed or built, or like [`Mime::to_header_value`] if that text can't go in a header. If
+/// neither works, nothing is encoded.
+///
+/// This is only available with the `headers` feature.
+///
+/// ## Example
+///
+/// ```rust
+/// use headers::{HeaderMap, HeaderMapExt};
+/// use mr_mime::{constants, ContentType};
+///
+/// let mut map = HeaderMap::new();
+/// map.insert("content-type", "text/html; charset=utf-8".parse().unwrap());
+///
+/// let ContentType(mime) = map.typed_get().unwrap();
+/// assert_eq!(mime.essence(), constants::TEXT_HTML);
+///
+/// map.typed_insert(ContentType::from(constants::APPLICATION_JSON));
+/// assert_eq!(map["content-type"], "application/json");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ContentType(pub MimeBuf);
+
+impl ContentType {
+    /// Borrow the MIME type of this header as a [`Mime`].
+    pub fn as_mime(&self) -> Mime<'_> {
+        self.0.as_mime()
+    }
+}
+
+impl Header for ContentType {
+    fn name() -> &'static HeaderName {
+        <headers::ContentType as Header>::name()
+    }
+
+    fn decode<'i, I: Iterator<Item = &'i HeaderValue>>(values: &mut I) -> Result<Self, Error> {
+        values
+            .next()
+            .and_then(|value| MimeBuf::parse(value.to_str().ok()?).ok())
+            .map(ContentType)
+            .ok_or_else(Error::invalid)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        // The text is kept as it was parsed, so it may have line breaks around the MIME type,
+        // which can't go in a header value. Writing it out as a header value drops them, and
+        // checks the parameters, so a value that can't go in a header is never mangled.
+        let value = HeaderValue::from_str(self.0.as_str()).ok().or_else(|| {
+            let value = self.as_mime().to_header_value().ok()?;
+            HeaderValue::from_str(&value).ok()
+        });
+        values.extend(value);
+    }
+}
+
+impl From<MimeBuf> for ContentType {
+    fn from(mime: MimeBuf) -> Self {
+        ContentType(mime)
+    }
+}
+
+impl From<Mime<'_>> for ContentType {
+    fn from(mime: Mime<'_>) -> Self {
+        ContentType(mime.into())
+    }
+}
+
+impl From<ContentType> for MimeBuf {
+    fn from(content_type: ContentType) -> Self {
+        content_type.0
+    }
+}
+
+impl fmt::Display for ContentType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+#[cfg(all(test, feature = "tables"))]
+mod content_type_test {
+    use super::*;
+    use crate::constants;
+
+    use headers::{HeaderMap, HeaderMapExt};
+    use std::vec::Vec;
+
+    #[test]
+    fn decodes_the_first_value() {
+        let mut map = HeaderMap::new();
+        assert_eq!(map.typed_get::<ContentType>(), None);
+
+        map.append(
+            "Content-Type",
+            HeaderValue::from_static("image/svg+xml; a=\"b c\""),
+        );
+        map.append("Content-Type", HeaderValue::from_static("text/plain"));
+
+        let ContentType(mime) = map.typed_get().unwrap();
+        assert_eq!(mime, Mime::parse("image/svg+xml; a=\"b c\"").unwrap());
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        for value in &[&b"text"[..], b"", b"/plain", b"text/pl\xffin"] {
+            let mut map = HeaderMap::new();
+            map.insert("content-type", HeaderValue::from_bytes(value).unwrap());
+            assert!(map.typed_try_get::<ContentType>().is_err());
+        }
+    }
+
+    #[test]
+    fn encodes_the_text() {
+        let mut map = HeaderMap::new();
+        map.typed_insert(ContentType::from(constants::TEXT_PLAIN));
+        map.typed_insert(ContentType(
+            MimeBuf::parse("text/html; charset=\"utf-8\"").unwrap(),
+        ));
+        assert_eq!(
+            map.get_all("content-type").iter().collect::<Vec<_>>(),
+            ["text/html; charset=\"utf-8\""]
+        );
+
+        let mut values = Vec::new();
+        ContentType(MimeBuf::parse("\r\ntext/plain;\r\n a=b").unwrap()).encode(&mut values);
+        assert_eq!(values, ["text/plain; a=b"]);
+
+        let mut values = Vec::new();
+        let mime = MimeBuf::parse("\r\ntext/plain; a=\"b \\\"c\\\"\"; d=\"\u{e9}\"").unwrap();
+        ContentType(mime).encode(&mut values);
+        assert_eq!(
+            values,
+            [HeaderValue::from_str("text/plain; a=\"b \\\"c\\\"\"; d=\"\u{e9}\"").unwrap()]
+        );
+    }
+}
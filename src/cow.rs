@@ -0,0 +1,243 @@
+//! A MIME type that is either borrowed or owned.
+
+use crate::{
+    BufferParameters, EitherIterator, Mime, MimeBuf, SliceParameters, Subtype, Suffix, Type,
+};
+
+use core::cmp;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+
+/// A MIME type that is either borrowed as a [`Mime`] or owned as a [`MimeBuf`].
+///
+/// This is useful for APIs that usually take a constant, but sometimes need a type that was
+/// built or parsed at runtime. Only the owned form allocates. Both forms compare, order and
+/// hash the same way as the equivalent [`Mime`].
+///
+/// ## Example
+///
+/// ```rust
+/// use mr_mime::{Mime, MimeCow, constants};
+///
+/// fn content_type(charset: Option<&str>) -> MimeCow<'static> {
+///     match charset.map(|charset| constants::TEXT_HTML.with_param("charset", charset)) {
+///         Some(Ok(mime)) => mime.into(),
+///         _ => constants::TEXT_HTML.into(),
+///     }
+/// }
+///
+/// assert_eq!(content_type(None), constants::TEXT_HTML);
+/// assert_eq!(content_type(Some("utf\n8")), constants::TEXT_HTML);
+/// assert_eq!(content_type(Some("utf-8")).to_string(), "text/html;charset=utf-8");
+/// assert_eq!(content_type(Some("utf-8")).essence(), constants::TEXT_HTML);
+/// ```
+#[derive(Debug, Clone)]
+pub enum MimeCow<'a> {
+    /// A borrowed MIME type.
+    Borrowed(Mime<'a>),
+
+    /// An owned MIME type.
+    Owned(MimeBuf),
+}
+
+impl<'a> MimeCow<'a> {
+    /// Borrow this MIME type as a [`Mime`].
+    pub fn as_mime(&self) -> Mime<'_> {
+        match self {
+            MimeCow::Borrowed(mime) => *mime,
+            MimeCow::Owned(buf) => buf.as_mime(),
+        }
+    }
+
+    /// Turn this MIME type into a [`MimeBuf`], copying it if it's borrowed.
+    pub fn into_owned(self) -> MimeBuf {
+        match self {
+            MimeCow::Borrowed(mime) => mime.to_owned_mime(),
+            MimeCow::Owned(buf) => buf,
+        }
+    }
+
+    /// Tell whether this MIME type is borrowed.
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self, MimeCow::Borrowed(_))
+    }
+
+    /// Get the type of this MIME type.
+    ///
+    /// This and the other accessors work the same as the ones on [`Mime`].
+    pub fn r#type(&self) -> Type<'_> {
+        match self {
+            MimeCow::Borrowed(mime) => mime.r#type(),
+            MimeCow::Owned(buf) => buf.r#type(),
+        }
+    }
+
+    /// Get the subtype of this MIME type.
+    pub fn subtype(&self) -> Subtype<'_> {
+        match self {
+            MimeCow::Borrowed(mime) => mime.subtype(),
+            MimeCow::Owned(buf) => buf.subtype(),
+        }
+    }
+
+    /// Get the suffix of this MIME type.
+    pub fn suffix(&self) -> Option<Suffix<'_>> {
+        match self {
+            MimeCow::Borrowed(mime) => mime.suffix(),
+            MimeCow::Owned(buf) => buf.suffix(),
+        }
+    }
+
+    /// Iterate over the parameters of this MIME type.
+    pub fn parameters(&self) -> EitherIterator<SliceParameters<'_>, BufferParameters<'_>> {
+        match self {
+            MimeCow::Borrowed(mime) => mime.parameters(),
+            MimeCow::Owned(buf) => buf.parameters(),
+        }
+    }
+
+    /// Get the value of the first parameter with the given key, compared case-insensitively.
+    pub fn get_parameter(&self, key: &str) -> Option<&[u8]> {
+        match self {
+            MimeCow::Borrowed(mime) => mime.get_parameter(key),
+            MimeCow::Owned(buf) => buf.get_parameter(key),
+        }
+    }
+
+    /// Get the "essence" of this MIME type, without the suffix or the parameters.
+    pub fn essence(&self) -> Mime<'_> {
+        self.as_mime().essence()
+    }
+}
+
+impl<'a> From<Mime<'a>> for MimeCow<'a> {
+    fn from(mime: Mime<'a>) -> Self {
+        MimeCow::Borrowed(mime)
+    }
+}
+
+impl From<MimeBuf> for MimeCow<'_> {
+    fn from(buf: MimeBuf) -> Self {
+        MimeCow::Owned(buf)
+    }
+}
+
+impl fmt::Display for MimeCow<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MimeCow::Borrowed(mime) => fmt::Display::fmt(mime, f),
+            MimeCow::Owned(buf) => fmt::Display::fmt(buf, f),
+        }
+    }
+}
+
+impl PartialEq for MimeCow<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_mime() == other.as_mime()
+    }
+}
+
+impl Eq for MimeCow<'_> {}
+
+impl PartialEq<Mime<'_>> for MimeCow<'_> {
+    fn eq(&self, other: &Mime<'_>) -> bool {
+        self.as_mime() == *other
+    }
+}
+
+impl PartialEq<MimeCow<'_>> for Mime<'_> {
+    fn eq(&self, other: &MimeCow<'_>) -> bool {
+        *self == other.as_mime()
+    }
+}
+
+impl PartialEq<MimeBuf> for MimeCow<'_> {
+    fn eq(&self, other: &MimeBuf) -> bool {
+        self.as_mime() == other.as_mime()
+    }
+}
+
+impl PartialEq<MimeCow<'_>> for MimeBuf {
+    fn eq(&self, other: &MimeCow<'_>) -> bool {
+        self.as_mime() == other.as_mime()
+    }
+}
+
+impl PartialOrd for MimeCow<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for MimeCow<'_> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.as_mime().cmp(&other.as_mime())
+    }
+}
+
+impl Hash for MimeCow<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_mime().hash(state);
+    }
+}
+
+#[cfg(all(test, feature = "tables"))]
+mod mime_cow_test {
+    use super::*;
+    use crate::constants;
+
+    use alloc::string::ToString;
+
+    #[test]
+    fn borrowed_and_owned_agree() {
+        let borrowed = MimeCow::from(Mime::parse("text/html; charset=utf-8").unwrap());
+        let owned = MimeCow::from(constants::TEXT_HTML.with_param("charset", "utf-8").unwrap());
+
+        assert!(borrowed.is_borrowed() && !owned.is_borrowed());
+        assert_eq!(borrowed, owned);
+        assert_eq!(borrowed.cmp(&owned), cmp::Ordering::Equal);
+        assert_eq!(borrowed.to_string(), owned.to_string());
+
+        for cow in &[&borrowed, &owned] {
+            assert_eq!(cow.r#type(), constants::types::TEXT);
+            assert_eq!(cow.subtype(), constants::subtypes::HTML);
+            assert_eq!(cow.suffix(), None);
+            assert_eq!(cow.essence(), constants::TEXT_HTML);
+            assert_eq!(cow.get_parameter("CHARSET"), Some(b"utf-8".as_ref()));
+            assert_eq!(cow.parameters().count(), 1);
+        }
+
+        assert_ne!(MimeCow::from(constants::TEXT_HTML), borrowed);
+    }
+
+    #[test]
+    fn into_owned_keeps_the_value() {
+        let owned = {
+            let source = alloc::string::String::from("image/svg+xml; a=b");
+            MimeCow::from(Mime::parse(&source).unwrap()).into_owned()
+        };
+
+        assert_eq!(owned, Mime::parse("image/svg+xml; a=b").unwrap());
+        assert_eq!(MimeCow::from(owned.clone()).into_owned(), owned);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn borrowed_and_owned_hash_the_same() {
+        use std::collections::HashSet;
+
+        let mut set = HashSet::new();
+        assert!(set.insert(MimeCow::from(
+            Mime::parse("text/plain; charset=utf-8").unwrap()
+        )));
+        assert!(!set.insert(MimeCow::from(
+            constants::TEXT_PLAIN
+                .with_param("charset", "utf-8")
+                .unwrap()
+        )));
+        assert!(set.insert(MimeCow::from(constants::TEXT_PLAIN)));
+
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&MimeCow::from(constants::TEXT_PLAIN.to_owned_mime())));
+    }
+}
@@ -0,0 +1,241 @@
+//! Splitting `data:` URLs as defined in the WHATWG Fetch Standard.
+
+use crate::{parameters_from_bytes, Mime, Parameters, ParseError};
+
+use core::ops::Range;
+
+/// The type that a media type starting with a semicolon is given.
+const TEXT_PLAIN: Mime<'static> = Mime::from_static("text/plain");
+
+/// The media type of a data URL that has none, or one that can't be parsed.
+const DEFAULT: Mime<'static> = Mime {
+    parameters: Parameters::Slice(&[("charset", b"US-ASCII")]),
+    ..TEXT_PLAIN
+};
+
+/// A `data:` URL, split into its media type and its payload.
+///
+/// This follows the [data: URL processing] rules of the Fetch Standard up to the point where the
+/// payload would be decoded: the payload is handed back as it appears in the URL, still
+/// percent-encoded and, if [`is_base64`](DataUrl::is_base64) is set, still base64-encoded.
+///
+/// Unlike the standard, a media type with parameters that aren't valid is not cleaned up, but
+/// replaced as a whole by the default of `text/plain;charset=US-ASCII`.
+///
+/// [data: URL processing]: https://fetch.spec.whatwg.org/#data-url-processor
+///
+/// ## Example
+///
+/// ```rust
+/// use mr_mime::{constants, DataUrl};
+///
+/// let url = DataUrl::parse("data:text/html;charset=utf-8,<p>hello</p>").unwrap();
+/// assert_eq!(url.mime().essence(), constants::TEXT_HTML);
+/// assert_eq!(url.mime().get_parameter("charset"), Some(&b"utf-8"[..]));
+/// assert!(!url.is_base64());
+/// assert_eq!(url.payload(), "<p>hello</p>");
+///
+/// let url = DataUrl::parse("data:;base64,AAAA").unwrap();
+/// assert_eq!(url.mime().to_string(), "text/plain;charset=US-ASCII");
+/// assert!(url.is_base64());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DataUrl<'a> {
+    /// The media type of the payload.
+    mime: Mime<'a>,
+
+    /// Whether the payload is base64-encoded.
+    base64: bool,
+
+    /// The payload, without the fragment.
+    payload: &'a str,
+
+    /// The index of the payload in the URL.
+    start: usize,
+}
+
+impl<'a> DataUrl<'a> {
+    /// Split a `data:` URL into its media type and its payload.
+    ///
+    /// The scheme is matched case-insensitively, and anything after a `#` is a fragment that
+    /// isn't part of the payload. This fails if the URL isn't a `data:` URL, or if it has no
+    /// comma to end the media type.
+    pub fn parse(url: &'a str) -> Result<Self, ParseError> {
+        // The URL parser strips leading and trailing C0 controls and spaces.
+        let trimmed = url.trim_start_matches(is_c0_control_or_space);
+        let start = url.len() - trimmed.len();
+        let trimmed = trimmed.trim_end_matches(is_c0_control_or_space);
+
+        let rest = match trimmed.get(..5) {
+            Some(scheme) if scheme.eq_ignore_ascii_case("data:") => &trimmed[5..],
+            _ => return Err(ParseError::NotDataUrl),
+        };
+        let rest = rest.find('#').map_or(rest, |hash| &rest[..hash]);
+        let comma = rest.find(',').ok_or(ParseError::MissingComma)?;
+
+        let (media_type, base64) = strip_base64(rest[..comma].trim_matches(is_ascii_whitespace));
+        let mime =
+            parse_media_type(media_type.trim_matches(is_ascii_whitespace)).unwrap_or(DEFAULT);
+
+        Ok(Self {
+            mime,
+            base64,
+            payload: &rest[comma + 1..],
+            start: start + 5 + comma + 1,
+        })
+    }
+
+    /// Get the media type of the payload.
+    ///
+    /// This is `text/plain;charset=US-ASCII` if the URL has no media type, or one that can't be
+    /// parsed.
+    pub fn mime(&self) -> Mime<'a> {
+        self.mime
+    }
+
+    /// Whether the media type ends with `;base64`, meaning the payload is base64-encoded.
+    pub fn is_base64(&self) -> bool {
+        self.base64
+    }
+
+    /// Get the payload as it appears in the URL.
+    pub fn payload(&self) -> &'a str {
+        self.payload
+    }
+
+    /// Get the range of the URL that the payload takes up.
+    pub fn payload_range(&self) -> Range<usize> {
+        self.start..self.start + self.payload.len()
+    }
+}
+
+/// Remove a trailing `;base64` from a media type, and tell whether there was one.
+fn strip_base64(media_type: &str) -> (&str, bool) {
+    let split = media_type.len().wrapping_sub(6);
+    if media_type.is_char_boundary(split) && media_type[split..].eq_ignore_ascii_case("base64") {
+        if let Some(rest) = media_type[..split].trim_end_matches(' ').strip_suffix(';') {
+            return (rest, true);
+        }
+    }
+
+    (media_type, false)
+}
+
+/// Parse the media type of a data URL, which is `text/plain` if only parameters are given.
+fn parse_media_type(media_type: &str) -> Option<Mime<'_>> {
+    match media_type.strip_prefix(';') {
+        Some(parameters) => Some(Mime {
+            parameters: parameters_from_bytes(parameters.as_bytes()).ok()?,
+            ..TEXT_PLAIN
+        }),
+        None => Mime::parse(media_type).ok(),
+    }
+}
+
+/// Is this a character that the URL parser strips from either end?
+fn is_c0_control_or_space(c: char) -> bool {
+    c <= ' '
+}
+
+/// Is this ASCII whitespace, as the Infra Standard defines it?
+fn is_ascii_whitespace(c: char) -> bool {
+    matches!(c, '\t' | '\n' | '\x0C' | '\r' | ' ')
+}
+
+#[cfg(all(test, feature = "tables"))]
+mod data_url_test {
+    use super::*;
+    use crate::constants;
+
+    #[test]
+    fn data_url_without_a_type() {
+        let url = DataUrl::parse("data:,hello").unwrap();
+        assert_eq!(url.mime(), DEFAULT);
+        assert_eq!(url.mime().essence(), constants::TEXT_PLAIN);
+        assert_eq!(url.mime().get_parameter("charset"), Some(&b"US-ASCII"[..]));
+        assert!(!url.is_base64());
+        assert_eq!(url.payload(), "hello");
+        assert_eq!(url.payload_range(), 6..11);
+    }
+
+    #[test]
+    fn data_url_with_a_type() {
+        let source = "data:text/html;charset=utf-8,<p>";
+        let url = DataUrl::parse(source).unwrap();
+        assert_eq!(url.mime(), Mime::parse("text/html;charset=utf-8").unwrap());
+        assert!(!url.is_base64());
+        assert_eq!(url.payload(), "<p>");
+        assert_eq!(&source[url.payload_range()], "<p>");
+    }
+
+    #[test]
+    fn data_url_with_only_base64() {
+        let url = DataUrl::parse("data:;base64,AAAA").unwrap();
+        assert_eq!(url.mime(), DEFAULT);
+        assert!(url.is_base64());
+        assert_eq!(url.payload(), "AAAA");
+        assert_eq!(url.payload_range(), 13..17);
+    }
+
+    #[test]
+    fn data_url_with_only_parameters() {
+        let url = DataUrl::parse("data:;charset=utf-8;base64,").unwrap();
+        assert_eq!(url.mime().essence(), constants::TEXT_PLAIN);
+        assert_eq!(url.mime().get_parameter("charset"), Some(&b"utf-8"[..]));
+        assert!(url.is_base64());
+        assert_eq!(url.payload(), "");
+    }
+
+    #[test]
+    fn data_url_whitespace_is_stripped() {
+        let source = " \tDATA: image/svg+xml ; BASE64 ,PHN2Zz4=#top \n";
+        let url = DataUrl::parse(source).unwrap();
+        assert_eq!(url.mime(), constants::IMAGE_SVG_XML);
+        assert!(url.is_base64());
+        assert_eq!(url.payload(), "PHN2Zz4=");
+        assert_eq!(&source[url.payload_range()], "PHN2Zz4=");
+
+        // Only a `;` that ends the media type counts.
+        let url = DataUrl::parse("data:text/plain base64,x").unwrap();
+        assert_eq!(url.mime(), DEFAULT);
+        assert!(!url.is_base64());
+    }
+
+    #[test]
+    fn data_url_invalid_type_uses_default() {
+        for source in &["data:text,x", "data:text/plain;a=\x01,x", "data: ;base64,x"] {
+            assert_eq!(
+                DataUrl::parse(source).unwrap().mime(),
+                DEFAULT,
+                "{}",
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn data_url_malformed() {
+        for source in &[
+            "data:",
+            "data:text/plain",
+            "data:;base64",
+            "data:text/plain#,x",
+        ] {
+            assert_eq!(
+                DataUrl::parse(source),
+                Err(ParseError::MissingComma),
+                "{}",
+                source
+            );
+        }
+
+        for source in &["", "dat", "http://example.com/,x", "data;,x", "dätä:,x"] {
+            assert_eq!(
+                DataUrl::parse(source),
+                Err(ParseError::NotDataUrl),
+                "{}",
+                source
+            );
+        }
+    }
+}
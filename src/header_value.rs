@@ -0,0 +1,138 @@
+//! Conversions between MIME types and header values of the `http` crate.
+
+use crate::{HeaderValueError, Mime, ParseError};
+
+use core::convert::TryFrom;
+
+use http::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+
+/// Parses the MIME type in a header value, borrowing its bytes.
+///
+/// This fails with [`ParseError::NonHttpCodepoints`] if the value has bytes that aren't visible
+/// ASCII, and with the usual errors of [`Mime::parse`] if it isn't a valid MIME type.
+///
+/// This is only available with the `http` feature.
+impl<'a> TryFrom<&'a HeaderValue> for Mime<'a> {
+    type Error = ParseError;
+
+    fn try_from(value: &'a HeaderValue) -> Result<Self, Self::Error> {
+        let text = value.to_str().map_err(|_| ParseError::NonHttpCodepoints)?;
+        Mime::parse(text)
+    }
+}
+
+/// Writes out a MIME type as a header value, in the same way as [`Mime::to_header_value`].
+///
+/// This fails if a parameter can't go in a header.
+///
+/// This is only available with the `http` feature.
+impl TryFrom<&Mime<'_>> for HeaderValue {
+    type Error = HeaderValueError;
+
+    fn try_from(mime: &Mime<'_>) -> Result<Self, Self::Error> {
+        let value = mime.to_header_value()?;
+        Ok(HeaderValue::try_from(value).expect("checked header values are always valid"))
+    }
+}
+
+impl<'a> Mime<'a> {
+    /// Parse the `Content-Type` header of a header map.
+    ///
+    /// This returns `None` if there is no `Content-Type` header, and parses the first one if
+    /// there are several. The MIME type borrows from the header map.
+    ///
+    /// This is only available with the `http` feature.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use http::header::{HeaderMap, CONTENT_TYPE};
+    /// use mr_mime::{constants, Mime};
+    ///
+    /// let mut headers = HeaderMap::new();
+    /// assert_eq!(Mime::from_content_type(&headers), None);
+    ///
+    /// headers.insert(CONTENT_TYPE, "text/html; charset=utf-8".parse().unwrap());
+    /// let mime = Mime::from_content_type(&headers).unwrap().unwrap();
+    /// assert_eq!(mime.essence(), constants::TEXT_HTML);
+    /// assert_eq!(mime.get_parameter("charset"), Some(&b"utf-8"[..]));
+    /// ```
+    pub fn from_content_type(headers: &'a HeaderMap) -> Option<Result<Self, ParseError>> {
+        headers.get(CONTENT_TYPE).map(Mime::try_from)
+    }
+}
+
+#[cfg(all(test, feature = "tables"))]
+mod header_value_test {
+    use super::*;
+    use crate::constants;
+
+    use std::string::ToString;
+
+    #[test]
+    fn constants_round_trip() {
+        for &mime in &[
+            constants::TEXT_PLAIN,
+            constants::APPLICATION_JSON,
+            constants::IMAGE_SVG_XML,
+            constants::APPLICATION_OCTET_STREAM,
+        ] {
+            let value = HeaderValue::try_from(&mime).unwrap();
+            assert_eq!(value, mime.to_string().as_str());
+            assert_eq!(Mime::try_from(&value), Ok(mime));
+        }
+    }
+
+    #[test]
+    fn parameters_round_trip() {
+        let mime = Mime::parse("multipart/form-data; boundary=\"a b\"; charset=utf-8").unwrap();
+        let value = HeaderValue::try_from(&mime).unwrap();
+        assert_eq!(
+            value,
+            "multipart/form-data; boundary=\"a b\"; charset=utf-8"
+        );
+
+        let parsed = Mime::try_from(&value).unwrap();
+        assert_eq!(parsed, mime);
+        assert_eq!(parsed.get_parameter("boundary"), Some(&b"a b"[..]));
+    }
+
+    #[test]
+    fn unrepresentable_mime_types() {
+        let mime = Mime::new(
+            constants::types::TEXT,
+            constants::subtypes::PLAIN,
+            None,
+            &[("charset", b"utf-8\r\nSet-Cookie: a=b")],
+        );
+        assert_eq!(
+            HeaderValue::try_from(&mime),
+            Err(HeaderValueError::ParameterValue(0))
+        );
+    }
+
+    #[test]
+    fn invalid_header_values() {
+        let value = HeaderValue::from_bytes(b"text/pl\xffin").unwrap();
+        assert_eq!(Mime::try_from(&value), Err(ParseError::NonHttpCodepoints));
+
+        let value = HeaderValue::from_static("text");
+        assert_eq!(Mime::try_from(&value), Err(ParseError::NoSlash));
+    }
+
+    #[test]
+    fn content_type_from_header_map() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(Mime::from_content_type(&headers), None);
+
+        headers.append(CONTENT_TYPE, HeaderValue::from_static("image/png"));
+        headers.append(CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+        assert_eq!(
+            Mime::from_content_type(&headers),
+            Some(Ok(constants::IMAGE_PNG))
+        );
+
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("/plain"));
+        assert!(matches!(Mime::from_content_type(&headers), Some(Err(_))));
+    }
+}
@@ -0,0 +1,150 @@
+//! Comma-separated lists of MIME types.
+
+use crate::{Mime, ParseError};
+
+use core::iter::FusedIterator;
+
+/// Parse a comma-separated list of MIME types, as found in many HTTP headers.
+///
+/// This returns an iterator over the MIME types in the list, which are parsed lazily and without
+/// allocating. Commas inside of quoted strings don't split MIME types, and empty elements are
+/// skipped, so a trailing comma is allowed. A malformed MIME type is reported as an error, but the
+/// MIME types after it can still be read.
+///
+/// Media ranges with weights are better read with [`parse_accept`](crate::accept::parse_accept).
+///
+/// ## Example
+///
+/// ```rust
+/// use mr_mime::{constants, parse_list, Mime, ParseError};
+///
+/// let mut list = parse_list("text/html, multipart/mixed; boundary=\"a,b\", nonsense,");
+///
+/// assert_eq!(list.next(), Some(Ok(constants::TEXT_HTML)));
+/// assert_eq!(
+///     list.next(),
+///     Some(Mime::parse("multipart/mixed; boundary=\"a,b\""))
+/// );
+/// assert_eq!(list.next(), Some(Err(ParseError::NoSlash)));
+/// assert_eq!(list.next(), None);
+/// ```
+pub fn parse_list(list: &str) -> MimeList<'_> {
+    MimeList { rest: list }
+}
+
+/// Iterator over the MIME types in a comma-separated list.
+///
+/// This is returned by [`parse_list`].
+#[derive(Debug, Clone)]
+pub struct MimeList<'a> {
+    /// The part of the list that hasn't been parsed yet.
+    rest: &'a str,
+}
+
+impl<'a> Iterator for MimeList<'a> {
+    type Item = Result<Mime<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        next_element(&mut self.rest).map(Mime::parse)
+    }
+}
+
+impl FusedIterator for MimeList<'_> {}
+
+/// Split the next non-empty element off of a comma-separated list.
+///
+/// Commas inside of quoted strings don't end an element, and the element is trimmed of spaces and
+/// tabs.
+pub(crate) fn next_element<'a>(rest: &mut &'a str) -> Option<&'a str> {
+    loop {
+        if rest.is_empty() {
+            return None;
+        }
+
+        // Find the next comma that isn't in a quoted string.
+        let mut quoted = false;
+        let mut escaped = false;
+        let mut end = rest.len();
+        for (i, b) in rest.bytes().enumerate() {
+            match b {
+                _ if escaped => escaped = false,
+                b'\\' if quoted => escaped = true,
+                b'"' => quoted = !quoted,
+                b',' if !quoted => {
+                    end = i;
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        let element = rest[..end].trim_matches(|c| c == ' ' || c == '\t');
+        *rest = rest.get(end + 1..).unwrap_or("");
+
+        // Empty elements are allowed, but don't mean anything.
+        if !element.is_empty() {
+            return Some(element);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "tables"))]
+mod list_test {
+    use super::*;
+    use crate::constants;
+
+    #[test]
+    fn parse_list_splits_on_commas() {
+        let mut list = parse_list("text/html,image/png ,\tapplication/json");
+        assert_eq!(list.next(), Some(Ok(constants::TEXT_HTML)));
+        assert_eq!(list.next(), Some(Ok(constants::IMAGE_PNG)));
+        assert_eq!(list.next(), Some(Ok(constants::APPLICATION_JSON)));
+        assert_eq!(list.next(), None);
+        assert_eq!(list.next(), None);
+    }
+
+    #[test]
+    fn parse_list_skips_empty_elements() {
+        let mut list = parse_list(" ,text/html, ,\t,,image/png,");
+        assert_eq!(list.next(), Some(Ok(constants::TEXT_HTML)));
+        assert_eq!(list.next(), Some(Ok(constants::IMAGE_PNG)));
+        assert_eq!(list.next(), None);
+
+        assert_eq!(parse_list("").next(), None);
+        assert_eq!(parse_list(" , ,").next(), None);
+    }
+
+    #[test]
+    fn parse_list_respects_quoted_commas() {
+        let mut list = parse_list(
+            "multipart/form-data; boundary=\"a,b\", text/plain;x=\"\\\",\";y=z, image/png",
+        );
+
+        let multipart = list.next().unwrap().unwrap();
+        assert_eq!(multipart.essence(), constants::MULTIPART_FORM_DATA);
+        assert_eq!(multipart.get_parameter("boundary"), Some(&b"a,b"[..]));
+
+        let plain = list.next().unwrap().unwrap();
+        assert!(plain
+            .parameters()
+            .eq([("x", &br#"\","#[..]), ("y", b"z")].iter().copied()));
+
+        assert_eq!(list.next(), Some(Ok(constants::IMAGE_PNG)));
+        assert_eq!(list.next(), None);
+
+        // An unterminated quoted string runs to the end of the list.
+        let mut list = parse_list("text/plain;x=\"a, image/png");
+        assert!(list.next().unwrap().is_ok());
+        assert_eq!(list.next(), None);
+    }
+
+    #[test]
+    fn parse_list_reports_errors_per_element() {
+        let mut list = parse_list("text, /plain, text/html, text/pl\u{e9}in");
+        assert_eq!(list.next(), Some(Err(ParseError::NoSlash)));
+        assert_eq!(list.next(), Some(Err(ParseError::MissingType)));
+        assert_eq!(list.next(), Some(Ok(constants::TEXT_HTML)));
+        assert_eq!(list.next(), Some(Err(ParseError::NonHttpCodepoints)));
+        assert_eq!(list.next(), None);
+    }
+}
@@ -0,0 +1,262 @@
+//! Conversions between MIME types and the `Mime` type of the `mime` crate, version 0.3.
+//!
+//! The two crates don't quite agree on what a MIME type is:
+//!
+//! - `mime` lowercases the names, the parameter keys and the value of `charset` when parsing,
+//!   while this crate keeps them as written and compares names and keys case-insensitively.
+//!   Comparing the two treats the value of `charset` case-insensitively, like `mime` does, and
+//!   every other value exactly.
+//! - `mime` compares parameters in order, while this crate ignores their order. Comparing the
+//!   two ignores the order as well.
+//! - `mime` doesn't support escapes in quoted strings, so a backslash is part of the value and a
+//!   quote always ends it. Values with a quote in them, empty values and values with control
+//!   characters, including tabs, can't be converted to `mime`.
+//! - [`MimeBuilder`] keeps one value for each key, so converting a `mime` type with a repeated
+//!   parameter keeps the last value in the place of the first.
+
+use crate::{from_utf8, is_token, unescape, InvalidMime, Mime, MimeBuf, MimeBuilder};
+
+use core::convert::TryFrom;
+use core::fmt::Write;
+use std::string::String;
+use std::vec::Vec;
+
+/// Copies a MIME type of the `mime` crate.
+///
+/// This is only available with the `compat-mime03` feature.
+impl From<&mime::Mime> for MimeBuf {
+    fn from(mime: &mime::Mime) -> Self {
+        let mut builder = MimeBuilder::new();
+        builder
+            .type_(mime.type_().as_str())
+            .subtype(mime.subtype().as_str());
+        if let Some(suffix) = mime.suffix() {
+            builder.suffix(suffix.as_str());
+        }
+        for (key, value) in mime.params() {
+            builder.param(key.as_str(), value.as_str());
+        }
+
+        builder
+            .build()
+            .expect("MIME types from the mime crate are always valid")
+    }
+}
+
+/// Converts a MIME type to one of the `mime` crate.
+///
+/// This fails if a part of the MIME type can't be represented by the `mime` crate, such as a
+/// parameter value with a quote in it.
+///
+/// This is only available with the `compat-mime03` feature.
+impl TryFrom<&Mime<'_>> for mime::Mime {
+    type Error = InvalidMime;
+
+    fn try_from(mime: &Mime<'_>) -> Result<Self, Self::Error> {
+        let (ty, subtype) = (mime.r#type().into_str(), mime.subtype().into_str());
+        let suffix = mime.suffix().map(|suffix| suffix.into_str());
+
+        // The `mime` crate splits the subtype at the last `+`.
+        if !is_token(ty) {
+            return Err(InvalidMime::Type);
+        } else if !is_token(subtype) || (suffix.is_none() && subtype.contains('+')) {
+            return Err(InvalidMime::Subtype);
+        } else if !suffix.map_or(true, |suffix| is_token(suffix) && !suffix.contains('+')) {
+            return Err(InvalidMime::Suffix);
+        }
+
+        let mut text = String::new();
+        write!(text, "{}/{}", ty, subtype).ok();
+        if let Some(suffix) = suffix {
+            write!(text, "+{}", suffix).ok();
+        }
+
+        for (i, (key, value, escaped)) in mime.escaped_parameters().enumerate() {
+            if !is_token(key) {
+                return Err(InvalidMime::ParameterKey(i));
+            }
+
+            let value = from_utf8(value).map_err(|_| InvalidMime::ParameterValue(i))?;
+            let value: String = unescape(value, escaped).collect();
+            if value.is_empty() || value.contains(|c: char| c == '"' || c.is_ascii_control()) {
+                return Err(InvalidMime::ParameterValue(i));
+            }
+
+            if is_token(&value) {
+                write!(text, "; {}={}", key, value).ok();
+            } else {
+                write!(text, "; {}=\"{}\"", key, value).ok();
+            }
+        }
+
+        Ok(text
+            .parse()
+            .expect("MIME types are checked before they are converted"))
+    }
+}
+
+/// Compares the parts of a MIME type with those of a MIME type of the `mime` crate.
+///
+/// This is only available with the `compat-mime03` feature.
+impl PartialEq<mime::Mime> for Mime<'_> {
+    fn eq(&self, other: &mime::Mime) -> bool {
+        let names_eq = self
+            .r#type()
+            .into_str()
+            .eq_ignore_ascii_case(other.type_().as_str())
+            && self
+                .subtype()
+                .into_str()
+                .eq_ignore_ascii_case(other.subtype().as_str())
+            && match (self.suffix(), other.suffix()) {
+                (Some(left), Some(right)) => left.into_str().eq_ignore_ascii_case(right.as_str()),
+                (None, None) => true,
+                _ => false,
+            };
+        if !names_eq {
+            return false;
+        }
+
+        // Match up the parameters in any order, crossing off each one once it's been used.
+        let mut params: Vec<_> = other.params().collect();
+        for (key, value, escaped) in self.escaped_parameters() {
+            let value = match from_utf8(value) {
+                Ok(value) => value,
+                Err(_) => return false,
+            };
+
+            let found = params.iter().position(|(k, v)| {
+                let v = v.as_str();
+                k.as_str().eq_ignore_ascii_case(key)
+                    && if key.eq_ignore_ascii_case("charset") {
+                        unescape(value, escaped)
+                            .map(|c| c.to_ascii_lowercase())
+                            .eq(v.chars().map(|c| c.to_ascii_lowercase()))
+                    } else {
+                        unescape(value, escaped).eq(v.chars())
+                    }
+            });
+
+            match found {
+                Some(i) => {
+                    params.swap_remove(i);
+                }
+                None => return false,
+            }
+        }
+
+        params.is_empty()
+    }
+}
+
+impl PartialEq<Mime<'_>> for mime::Mime {
+    fn eq(&self, other: &Mime<'_>) -> bool {
+        other == self
+    }
+}
+
+#[cfg(all(test, feature = "tables"))]
+mod mime03_test {
+    use super::*;
+    use crate::constants;
+
+    #[test]
+    fn constants_convert_both_ways() {
+        for &(ours, theirs) in &[
+            (constants::TEXT_PLAIN, &mime::TEXT_PLAIN),
+            (constants::TEXT_HTML, &mime::TEXT_HTML),
+            (constants::APPLICATION_JSON, &mime::APPLICATION_JSON),
+            (constants::IMAGE_PNG, &mime::IMAGE_PNG),
+            (constants::IMAGE_SVG_XML, &mime::IMAGE_SVG),
+            (constants::MULTIPART_FORM_DATA, &mime::MULTIPART_FORM_DATA),
+            (
+                constants::APPLICATION_OCTET_STREAM,
+                &mime::APPLICATION_OCTET_STREAM,
+            ),
+        ] {
+            assert_eq!(ours, *theirs);
+            assert_eq!(*theirs, ours);
+            assert_eq!(MimeBuf::from(theirs), ours);
+            assert_eq!(mime::Mime::try_from(&ours).as_ref(), Ok(theirs));
+        }
+    }
+
+    #[test]
+    fn parameters_convert_both_ways() {
+        let theirs: mime::Mime = "text/plain; charset=UTF-8; Format=Flowed".parse().unwrap();
+        let ours = Mime::parse("TEXT/Plain;format=Flowed;charset=utf-8").unwrap();
+        assert_eq!(ours, theirs);
+        assert_eq!(theirs, ours);
+
+        let buf = MimeBuf::from(&theirs);
+        assert_eq!(buf.as_mime(), theirs);
+        assert_eq!(buf.get_parameter("charset"), Some(&b"utf-8"[..]));
+
+        // The `mime` crate itself cares about the order of the parameters.
+        let converted = mime::Mime::try_from(&ours).unwrap();
+        assert_eq!(converted, ours);
+        assert_ne!(converted, theirs);
+        assert_eq!(converted.get_param("format").unwrap(), "Flowed");
+
+        let ours = Mime::parse(r#"multipart/form-data; boundary="a b\\c""#).unwrap();
+        let converted = mime::Mime::try_from(&ours).unwrap();
+        assert_eq!(converted.get_param("boundary").unwrap(), r"a b\c");
+        assert_eq!(ours, converted);
+        assert_eq!(MimeBuf::from(&converted), ours);
+    }
+
+    #[test]
+    fn suffixes_convert_both_ways() {
+        let theirs: mime::Mime = "application/vnd.api+json".parse().unwrap();
+        let ours = MimeBuf::from(&theirs);
+        assert_eq!(ours.subtype().into_str(), "vnd.api");
+        assert_eq!(ours.suffix().map(|suffix| suffix.into_str()), Some("json"));
+        assert_eq!(mime::Mime::try_from(&ours.as_mime()).unwrap(), theirs);
+    }
+
+    #[test]
+    fn differences_are_not_equal() {
+        let theirs: mime::Mime = "text/plain; format=flowed".parse().unwrap();
+        for ours in &[
+            "text/html;format=flowed",
+            "text/plain+xml;format=flowed",
+            "text/plain;format=Flowed",
+            "text/plain",
+            "text/plain;format=flowed;charset=utf-8",
+            "text/plain;format=flowed;format=flowed",
+        ] {
+            assert_ne!(Mime::parse(ours).unwrap(), theirs, "{}", ours);
+        }
+    }
+
+    #[test]
+    fn unrepresentable_mime_types() {
+        let quoted = Mime::parse(r#"text/plain; title="say \"hi\"""#).unwrap();
+        assert_eq!(
+            mime::Mime::try_from(&quoted),
+            Err(InvalidMime::ParameterValue(0))
+        );
+
+        let tab = Mime::new(
+            constants::types::TEXT,
+            constants::subtypes::PLAIN,
+            None,
+            &[("charset", b"utf-8"), ("title", b"a\tb")],
+        );
+        assert_eq!(
+            mime::Mime::try_from(&tab),
+            Err(InvalidMime::ParameterValue(1))
+        );
+
+        let key = Mime::new(
+            constants::types::TEXT,
+            constants::subtypes::PLAIN,
+            None,
+            &[("a b", b"c")],
+        );
+        assert_eq!(
+            mime::Mime::try_from(&key),
+            Err(InvalidMime::ParameterKey(0))
+        );
+    }
+}
@@ -0,0 +1,42 @@
+//! Stand-ins for the generated tables when the `tables` feature is disabled.
+//!
+//! The interned name types are uninhabited, so every name is stored as a dynamic string.
+
+macro_rules! uninhabited_interns {
+    ($($name: ident),*) => {
+        $(
+            #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            pub(crate) enum $name {}
+
+            impl core::str::FromStr for $name {
+                type Err = crate::InvalidName;
+
+                fn from_str(_: &str) -> Result<Self, Self::Err> {
+                    Err(crate::InvalidName)
+                }
+            }
+
+            impl core::convert::TryFrom<&[u8]> for $name {
+                type Error = crate::InvalidName;
+
+                fn try_from(_: &[u8]) -> Result<Self, Self::Error> {
+                    Err(crate::InvalidName)
+                }
+            }
+
+            impl AsRef<str> for $name {
+                fn as_ref(&self) -> &str {
+                    match *self {}
+                }
+            }
+
+            impl From<$name> for &'static str {
+                fn from(name: $name) -> Self {
+                    match name {}
+                }
+            }
+        )*
+    };
+}
+
+uninhabited_interns!(TypeIntern, SubtypeIntern, SuffixIntern);
@@ -0,0 +1,122 @@
+//! Percent-encoding as defined in RFC 3986.
+
+use core::fmt;
+use core::iter::FusedIterator;
+
+/// Percent-decode a string of bytes.
+///
+/// Percent signs that aren't followed by two hex digits are passed through as is. This is
+/// the counterpart to [`Mime::to_percent_encoded`](crate::Mime::to_percent_encoded).
+///
+/// ## Example
+///
+/// ```rust
+/// use mr_mime::{Mime, constants, percent_decode};
+///
+/// let mut buffer = [0u8; 64];
+/// let mut len = 0;
+/// for (slot, byte) in buffer.iter_mut().zip(percent_decode(b"text%2Fplain")) {
+///     *slot = byte;
+///     len += 1;
+/// }
+///
+/// assert_eq!(Mime::parse_bytes(&buffer[..len]), Ok(constants::TEXT_PLAIN));
+/// ```
+pub fn percent_decode(bytes: &[u8]) -> PercentDecode<'_> {
+    PercentDecode {
+        bytes: bytes.iter(),
+    }
+}
+
+/// Iterator that percent-decodes a string of bytes.
+///
+/// This is returned by [`percent_decode`].
+#[derive(Debug, Clone)]
+pub struct PercentDecode<'a> {
+    /// The bytes left to decode.
+    bytes: core::slice::Iter<'a, u8>,
+}
+
+impl Iterator for PercentDecode<'_> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let byte = *self.bytes.next()?;
+        if byte != b'%' {
+            return Some(byte);
+        }
+
+        // Only consume the next two bytes if they are valid hex digits.
+        let mut lookahead = self.bytes.clone();
+        let high = lookahead.next().and_then(|&b| hex_value(b));
+        let low = lookahead.next().and_then(|&b| hex_value(b));
+
+        match (high, low) {
+            (Some(high), Some(low)) => {
+                self.bytes = lookahead;
+                Some(high << 4 | low)
+            }
+            _ => Some(b'%'),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.bytes.len();
+        ((len + 2) / 3, Some(len))
+    }
+}
+
+impl FusedIterator for PercentDecode<'_> {}
+
+/// A writer that percent-encodes everything written to it before passing it on.
+///
+/// Only the unreserved characters of RFC 3986 are left as is.
+pub(crate) struct PercentEncode<'a, W: ?Sized>(pub(crate) &'a mut W);
+
+impl<W: fmt::Write + ?Sized> fmt::Write for PercentEncode<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+        // Write out runs of unreserved characters in one go.
+        let mut start = 0;
+        for (i, &byte) in s.as_bytes().iter().enumerate() {
+            if is_unreserved(byte) {
+                continue;
+            }
+
+            // Unreserved characters are ASCII, so the run is on character boundaries.
+            if start < i {
+                self.0.write_str(&s[start..i])?;
+            }
+            start = i + 1;
+
+            self.0.write_char('%')?;
+            self.0.write_char(HEX[usize::from(byte >> 4)] as char)?;
+            self.0.write_char(HEX[usize::from(byte & 0xF)] as char)?;
+        }
+
+        if start < s.len() {
+            self.0.write_str(&s[start..])?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether this byte is an unreserved character in RFC 3986.
+fn is_unreserved(b: u8) -> bool {
+    matches!(
+        b,
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~'
+    )
+}
+
+/// Get the value of an ASCII hex digit.
+fn hex_value(b: u8) -> Option<u8> {
+    match b {
+        b'0'..=b'9' => Some(b - b'0'),
+        b'a'..=b'f' => Some(b - b'a' + 10),
+        b'A'..=b'F' => Some(b - b'A' + 10),
+        _ => None,
+    }
+}
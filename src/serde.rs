@@ -0,0 +1,602 @@
+//! Support for the `serde` crate.
+//!
+//! [`Mime`] and [`MimeBuf`] implement `Serialize` and `Deserialize` themselves. This module
+//! holds helpers for fields that are kept as strings, but should still hold MIME types.
+//!
+//! This is only available with the `serde` feature.
+
+use crate::{Mime, MimeBuf, MimeBuilder, ParseError};
+#[cfg(feature = "tables")]
+use crate::{Name, Subtype, Suffix, Type};
+
+use core::fmt;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use serde::de::{self, Deserialize, Deserializer, MapAccess, Unexpected, Visitor};
+use serde::ser::{self, Serialize, Serializer};
+
+/// The fields of the structured form of a MIME type.
+const FIELDS: &[&str] = &["type", "subtype", "suffix", "parameters"];
+
+/// Serializes a MIME type as the string that [`Display`](fmt::Display) writes.
+///
+/// Parameter values that aren't tokens are quoted and escaped, so the string parses back into
+/// the same parameters. The parameters are checked like in [`Mime::write_header_value`] first,
+/// and serializing fails if one of them can't be written out without being changed, such as a
+/// value with a line break in it.
+///
+/// This is only available with the `serde` feature.
+///
+/// ## Example
+///
+/// ```rust
+/// use mr_mime::Mime;
+///
+/// let mime = Mime::parse("text/html; charset=utf-8").unwrap();
+/// assert_eq!(
+///     serde_json::to_string(&mime).unwrap(),
+///     r#""text/html;charset=utf-8""#
+/// );
+///
+/// let mime = Mime::parse(r#"multipart/form-data; boundary="a b""#).unwrap();
+/// assert_eq!(
+///     serde_json::to_string(&mime).unwrap(),
+///     r#""multipart/form-data;boundary=\"a b\"""#
+/// );
+/// ```
+impl Serialize for Mime<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.check_header_value().map_err(ser::Error::custom)?;
+        serializer.collect_str(self)
+    }
+}
+
+/// Deserializes a MIME type from a string, borrowing from the input.
+///
+/// The string is parsed the same way as [`Mime::parse`], without copying it. This only works
+/// if the deserializer can lend out the string, which `serde_json` does for strings without
+/// escapes when reading from a `&str` or a byte slice. Otherwise, the MIME type can only be
+/// deserialized if it has no parameters and all of its names are known, and [`MimeBuf`] should
+/// be used instead.
+///
+/// This is only available with the `serde` feature.
+///
+/// ## Example
+///
+/// ```rust
+/// use mr_mime::{constants, Mime};
+///
+/// let input = String::from(r#"["text/html; charset=utf-8", "image/png"]"#);
+/// let types: Vec<Mime<'_>> = serde_json::from_str(&input).unwrap();
+///
+/// assert_eq!(types[0].essence(), constants::TEXT_HTML);
+/// assert_eq!(types[0].get_parameter("charset"), Some(&b"utf-8"[..]));
+/// assert_eq!(types[1], constants::IMAGE_PNG);
+/// ```
+impl<'de: 'a, 'a> Deserialize<'de> for Mime<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(MimeVisitor)
+    }
+}
+
+/// Visits a string that a MIME type can borrow.
+struct MimeVisitor;
+
+impl<'de> Visitor<'de> for MimeVisitor {
+    type Value = Mime<'de>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a borrowed MIME type string")
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        Mime::parse(v).map_err(|error| E::custom(Invalid(error, v)))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        // The string can't be borrowed, but it may not need to be.
+        let mime = Mime::parse(v).map_err(|error| E::custom(Invalid(error, v)))?;
+        to_static(&mime).ok_or_else(|| E::invalid_type(Unexpected::Str(v), &self))
+    }
+}
+
+/// Get a MIME type that doesn't borrow anything, if it has no parameters and only known names.
+#[cfg(feature = "tables")]
+fn to_static(mime: &Mime<'_>) -> Option<Mime<'static>> {
+    if mime.parameters().next().is_some() {
+        return None;
+    }
+
+    let (ty, subtype, suffix) = mime.interned_parts()?;
+    Some(Mime::new(
+        Type(Name::Interned(ty)),
+        Subtype(Name::Interned(subtype)),
+        suffix.map(|suffix| Suffix(Name::Interned(suffix))),
+        &[],
+    ))
+}
+
+/// Get a MIME type that doesn't borrow anything, which needs the tables of known names.
+#[cfg(not(feature = "tables"))]
+fn to_static(_mime: &Mime<'_>) -> Option<Mime<'static>> {
+    None
+}
+
+/// Serializes a MIME type the same way as [`Mime`], as the string that
+/// [`Display`](fmt::Display) writes.
+///
+/// This is only available with the `serde` feature.
+///
+/// ## Example
+///
+/// ```rust
+/// use mr_mime::MimeBuf;
+///
+/// let mime = MimeBuf::parse("multipart/form-data; boundary=\"a b\"").unwrap();
+/// let json = serde_json::to_string(&mime).unwrap();
+/// assert_eq!(json, r#""multipart/form-data;boundary=\"a b\"""#);
+///
+/// let back: MimeBuf = serde_json::from_str(&json).unwrap();
+/// assert_eq!(back, mime);
+/// ```
+impl Serialize for MimeBuf {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.as_mime().serialize(serializer)
+    }
+}
+
+/// Deserializes a MIME type from either a string or a map.
+///
+/// A string is parsed the same way as [`MimeBuf::parse`]. A map has a `type`, a `subtype`, an
+/// optional `suffix` and an optional map of `parameters`, and is put together the same way as
+/// [`MimeBuilder::build`], so the values don't have to be quoted. Parameters keep the order they
+/// appear in.
+///
+/// Formats that aren't human-readable, like `postcard`, often can't tell what's coming next, so
+/// for them the MIME type is always read as a string. Nothing is borrowed from the input, so
+/// this works where `DeserializeOwned` is needed.
+///
+/// This is only available with the `serde` feature.
+///
+/// ## Example
+///
+/// ```rust
+/// use mr_mime::{constants, MimeBuf};
+///
+/// let parsed: MimeBuf = serde_json::from_str(r#""text/html; charset=utf-8""#).unwrap();
+/// let built: MimeBuf = serde_json::from_str(
+///     r#"{"type": "text", "subtype": "html", "parameters": {"charset": "utf-8"}}"#,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(parsed, built);
+/// assert_eq!(built.essence(), constants::TEXT_HTML);
+/// ```
+impl<'de> Deserialize<'de> for MimeBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(MimeBufVisitor)
+        } else {
+            deserializer.deserialize_string(MimeBufVisitor)
+        }
+    }
+}
+
+/// Visits either form of a MIME type.
+struct MimeBufVisitor;
+
+impl<'de> Visitor<'de> for MimeBufVisitor {
+    type Value = MimeBuf;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a MIME type string or a map with a type and a subtype")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        MimeBuf::parse(v).map_err(|error| E::custom(Invalid(error, v)))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut ty = None::<String>;
+        let mut subtype = None::<String>;
+        let mut suffix = None::<Option<String>>;
+        let mut parameters = None::<Option<Parameters>>;
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "type" if ty.is_some() => return Err(de::Error::duplicate_field("type")),
+                "type" => ty = Some(map.next_value()?),
+                "subtype" if subtype.is_some() => {
+                    return Err(de::Error::duplicate_field("subtype"))
+                }
+                "subtype" => subtype = Some(map.next_value()?),
+                "suffix" if suffix.is_some() => return Err(de::Error::duplicate_field("suffix")),
+                "suffix" => suffix = Some(map.next_value()?),
+                "parameters" if parameters.is_some() => {
+                    return Err(de::Error::duplicate_field("parameters"))
+                }
+                "parameters" => parameters = Some(map.next_value()?),
+                other => return Err(de::Error::unknown_field(other, FIELDS)),
+            }
+        }
+
+        let mut builder = MimeBuilder::new();
+        builder
+            .type_(&ty.ok_or_else(|| de::Error::missing_field("type"))?)
+            .subtype(&subtype.ok_or_else(|| de::Error::missing_field("subtype"))?);
+        if let Some(suffix) = suffix.flatten() {
+            builder.suffix(&suffix);
+        }
+        for (key, value) in parameters.flatten().map_or_else(Vec::new, |p| p.0) {
+            builder.param(&key, &value);
+        }
+
+        builder.build().map_err(de::Error::custom)
+    }
+}
+
+/// The parameters of the structured form, in the order they appear.
+struct Parameters(Vec<(String, String)>);
+
+impl<'de> Deserialize<'de> for Parameters {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ParametersVisitor;
+
+        impl<'de> Visitor<'de> for ParametersVisitor {
+            type Value = Parameters;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a map of parameter keys to values")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut parameters = Vec::with_capacity(map.size_hint().unwrap_or(0));
+                while let Some(entry) = map.next_entry()? {
+                    parameters.push(entry);
+                }
+                Ok(Parameters(parameters))
+            }
+        }
+
+        deserializer.deserialize_map(ParametersVisitor)
+    }
+}
+
+/// Serialize and deserialize a `String` that holds a MIME type.
+///
+/// This is meant for `#[serde(with = "mr_mime::serde::string")]` on `String` fields. The field
+/// is checked the same way as [`MimeBuf::parse`] when it's serialized and when it's
+/// deserialized, but is kept as it is.
+///
+/// ## Example
+///
+/// ```rust
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, Serialize, Deserialize)]
+/// struct Upload {
+///     #[serde(with = "mr_mime::serde::string")]
+///     content_type: String,
+/// }
+///
+/// let upload: Upload = serde_json::from_str(r#"{"content_type": "image/png"}"#).unwrap();
+/// assert_eq!(upload.content_type, "image/png");
+///
+/// let error = serde_json::from_str::<Upload>(r#"{"content_type": "png"}"#).unwrap_err();
+/// assert!(error.to_string().starts_with(r#"no slash in MIME type: "png""#));
+/// ```
+pub mod string {
+    use super::Invalid;
+    use crate::Mime;
+
+    use alloc::string::String;
+
+    use serde::de::{self, Deserialize, Deserializer};
+    use serde::ser::{self, Serializer};
+
+    /// Serialize a string that holds a MIME type, failing if it doesn't parse.
+    pub fn serialize<S: Serializer>(value: &str, serializer: S) -> Result<S::Ok, S::Error> {
+        match Mime::parse(value) {
+            Ok(_) => serializer.serialize_str(value),
+            Err(error) => Err(ser::Error::custom(Invalid(error, value))),
+        }
+    }
+
+    /// Deserialize a string that holds a MIME type, failing if it doesn't parse.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        match Mime::parse(&value) {
+            Ok(_) => Ok(value),
+            Err(error) => Err(de::Error::custom(Invalid(error, &value))),
+        }
+    }
+}
+
+/// Displays a parse error along with the text that failed to parse.
+struct Invalid<'a>(ParseError, &'a str);
+
+impl fmt::Display for Invalid<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {:?}", self.0, self.1)
+    }
+}
+
+#[cfg(all(test, feature = "tables"))]
+mod serde_test {
+    use super::*;
+    use crate::{constants, InvalidMime};
+
+    use alloc::format;
+    use alloc::string::ToString;
+
+    fn from_json(json: &str) -> Result<MimeBuf, String> {
+        serde_json::from_str(json).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn deserializes_strings() {
+        assert_eq!(from_json(r#""text/plain""#).unwrap(), constants::TEXT_PLAIN);
+        assert_eq!(
+            from_json(r#""image/svg+xml; a=\"b c\"""#).unwrap(),
+            Mime::parse("image/svg+xml; a=\"b c\"").unwrap()
+        );
+        assert!(from_json(r#""text""#).is_err());
+        assert!(from_json("42")
+            .unwrap_err()
+            .contains("a MIME type string or a map"));
+    }
+
+    #[test]
+    fn deserializes_maps() {
+        let mime = from_json(
+            r#"{
+                "type": "image",
+                "subtype": "svg",
+                "suffix": "xml",
+                "parameters": {"b": "x y", "a": "1"}
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(mime.to_string(), "image/svg+xml;b=\"x y\";a=1");
+
+        assert_eq!(
+            from_json(r#"{"subtype": "html", "type": "text", "suffix": null}"#).unwrap(),
+            constants::TEXT_HTML
+        );
+        assert_eq!(
+            from_json(r#"{"type": "text", "subtype": "html", "parameters": {}}"#).unwrap(),
+            constants::TEXT_HTML
+        );
+    }
+
+    #[test]
+    fn rejects_bad_maps() {
+        for (json, error) in &[
+            (r#"{"type": "text"}"#, "missing field `subtype`"),
+            (r#"{"subtype": "plain"}"#, "missing field `type`"),
+            (
+                r#"{"type": "text", "type": "text", "subtype": "plain"}"#,
+                "duplicate field `type`",
+            ),
+            (
+                r#"{"type": "text", "subtype": "plain", "charset": "utf-8"}"#,
+                "unknown field `charset`",
+            ),
+            (
+                r#"{"type": "text", "subtype": "plain", "parameters": ["a", "b"]}"#,
+                "a map of parameter keys to values",
+            ),
+        ] {
+            assert!(from_json(json).unwrap_err().contains(error), "{}", json);
+        }
+
+        for (json, error) in &[
+            (
+                r#"{"type": "text", "subtype": "pl ain"}"#,
+                InvalidMime::Subtype,
+            ),
+            (
+                r#"{"type": "text", "subtype": "plain", "parameters": {"a b": "c"}}"#,
+                InvalidMime::ParameterKey(0),
+            ),
+        ] {
+            assert!(from_json(json).unwrap_err().starts_with(&error.to_string()));
+        }
+    }
+
+    #[test]
+    fn mime_deserializes_borrowed_strings() {
+        let input = String::from(r#""x-custom/thing+json; charset=utf-8""#);
+        let mime: Mime<'_> = serde_json::from_str(&input).unwrap();
+        assert_eq!(
+            mime,
+            Mime::parse("x-custom/thing+json; charset=utf-8").unwrap()
+        );
+
+        // The names point into the input rather than a copy.
+        let ty = mime.r#type().into_str();
+        assert!(input.as_bytes().as_ptr_range().contains(&ty.as_ptr()));
+
+        let mime: Mime<'_> = serde_json::from_slice(b"\"text/plain\"").unwrap();
+        assert_eq!(mime, constants::TEXT_PLAIN);
+    }
+
+    #[test]
+    fn mime_deserializes_owned_strings_without_parameters() {
+        let mut reader = serde_json::Deserializer::from_reader(&b"\"Image/SVG+XML\""[..]);
+        let mime: Mime<'static> = Mime::deserialize(&mut reader).unwrap();
+        assert_eq!(mime, constants::IMAGE_SVG_XML);
+
+        // Escapes mean that the string can't be borrowed from the input either.
+        let mime: Mime<'_> = serde_json::from_str(r#""text\/html""#).unwrap();
+        assert_eq!(mime, constants::TEXT_HTML);
+
+        for json in &[r#""text/html; charset=utf-8""#, r#""x-custom/thing""#] {
+            let mut reader = serde_json::Deserializer::from_reader(json.as_bytes());
+            let error = Mime::deserialize(&mut reader).unwrap_err().to_string();
+            assert!(error.contains("a borrowed MIME type string"), "{}", error);
+        }
+        let error = serde_json::from_str::<Mime<'_>>(r#""text/plain; a=\"b\"""#).unwrap_err();
+        assert!(error.to_string().contains("a borrowed MIME type string"));
+    }
+
+    #[test]
+    fn mime_deserialize_reports_parse_errors() {
+        let error = serde_json::from_str::<Mime<'_>>(r#""text""#).unwrap_err();
+        assert!(error.to_string().starts_with("no slash in MIME type"));
+
+        let mut reader = serde_json::Deserializer::from_reader(&b"\"/plain\""[..]);
+        let error = Mime::deserialize(&mut reader).unwrap_err();
+        assert!(error.to_string().starts_with("missing MIME type"));
+
+        let error = serde_json::from_str::<Mime<'_>>("42").unwrap_err();
+        assert!(error.to_string().contains("a borrowed MIME type string"));
+    }
+
+    #[test]
+    fn mime_serializes_through_display() {
+        for source in &[
+            "text/plain",
+            "image/svg+xml; charset=utf-8",
+            "X-Custom/Thing",
+        ] {
+            let mime = Mime::parse(source).unwrap();
+            let json = serde_json::to_string(&mime).unwrap();
+            assert_eq!(json, format!("\"{}\"", mime));
+
+            let back: Mime<'_> = serde_json::from_str(&json).unwrap();
+            assert_eq!(back, mime);
+        }
+
+        // Like `Display`, this quotes the values that aren't tokens.
+        let mime = Mime::parse("multipart/form-data; boundary=\"a b\"").unwrap();
+        assert_eq!(
+            serde_json::to_string(&mime).unwrap(),
+            r#""multipart/form-data;boundary=\"a b\"""#
+        );
+    }
+
+    #[test]
+    fn mime_serializes_quoted_values_that_parse_back() {
+        for source in &[
+            r#"multipart/form-data; boundary="a b""#,
+            r#"text/plain; title="say \"hi\" \\o/"; charset=utf-8"#,
+            "text/plain; title=\"caf\u{e9}\"",
+        ] {
+            let mime = Mime::parse(source).unwrap();
+            let json = serde_json::to_string(&mime).unwrap();
+            let back: MimeBuf = serde_json::from_str(&json).unwrap();
+            assert_eq!(back, mime, "{}", json);
+        }
+
+        let built = Mime::new(
+            constants::types::TEXT,
+            constants::subtypes::PLAIN,
+            None,
+            &[("title", b"say \"hi\"")],
+        );
+        let json = serde_json::to_string(&built).unwrap();
+        assert_eq!(json, r#""text/plain;title=\"say \\\"hi\\\"\"""#);
+        let back: MimeBuf = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.as_mime().parameters().count(), 1);
+
+        let broken = Mime::new(
+            constants::types::TEXT,
+            constants::subtypes::PLAIN,
+            None,
+            &[("charset", b"utf-8"), ("evil", b"a\r\nSet-Cookie: b")],
+        );
+        let error = serde_json::to_string(&broken).unwrap_err();
+        assert!(error.to_string().contains("parameter 1"), "{}", error);
+    }
+
+    #[test]
+    fn mime_buf_round_trips_through_json() {
+        for source in &[
+            "text/plain",
+            "Image/SVG+xml; charset=\"utf-8\"",
+            "multipart/form-data; boundary=\"a;b \\\"c\\\"\"",
+        ] {
+            let mime = MimeBuf::parse(*source).unwrap();
+            let json = serde_json::to_string(&mime).unwrap();
+            assert_eq!(json, serde_json::to_string(&mime.as_mime()).unwrap());
+
+            let back = from_json(&json).unwrap();
+            assert_eq!(back, mime);
+            assert_eq!(back.as_str(), mime.to_string());
+
+            // Nothing is borrowed, so this works from a reader as well.
+            let back: MimeBuf = serde_json::from_reader(json.as_bytes()).unwrap();
+            assert_eq!(back, mime);
+        }
+    }
+
+    #[test]
+    fn mime_buf_round_trips_through_postcard() {
+        let mime = MimeBuf::parse("image/svg+xml; charset=\"utf 8\"").unwrap();
+        let bytes = postcard::to_allocvec(&(&mime, 7u8)).unwrap();
+        let (back, seven): (MimeBuf, u8) = postcard::from_bytes(&bytes).unwrap();
+        assert_eq!(back, mime);
+        assert_eq!(back.as_str(), mime.to_string());
+        assert_eq!(seven, 7);
+
+        // A borrowed `Mime` reads the same bytes without copying them.
+        let back: (Mime<'_>, u8) = postcard::from_bytes(&bytes).unwrap();
+        assert_eq!(back.0, mime);
+
+        let bytes = postcard::to_allocvec("text").unwrap();
+        assert!(postcard::from_bytes::<MimeBuf>(&bytes).is_err());
+    }
+
+    #[test]
+    fn errors_show_the_offending_text() {
+        assert_eq!(
+            from_json(r#""text""#).unwrap_err(),
+            "no slash in MIME type: \"text\" at line 1 column 6"
+        );
+        assert!(from_json(r#""text/pl ain""#)
+            .unwrap_err()
+            .starts_with("MIME type contains non-HTTP codepoints: \"text/pl ain\""));
+
+        let error = serde_json::from_str::<Mime<'_>>(r#""/plain""#).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("missing MIME type: \"/plain\""));
+    }
+
+    #[test]
+    fn string_helpers_check_fields() {
+        #[derive(Debug, serde::Serialize, serde::Deserialize)]
+        struct Upload {
+            #[serde(with = "crate::serde::string")]
+            content_type: String,
+        }
+
+        let upload: Upload =
+            serde_json::from_str(r#"{"content_type": "text/html; charset=utf-8"}"#).unwrap();
+        assert_eq!(upload.content_type, "text/html; charset=utf-8");
+        assert_eq!(
+            serde_json::to_string(&upload).unwrap(),
+            r#"{"content_type":"text/html; charset=utf-8"}"#
+        );
+
+        let bytes = postcard::to_allocvec(&upload).unwrap();
+        let back: Upload = postcard::from_bytes(&bytes).unwrap();
+        assert_eq!(back.content_type, upload.content_type);
+
+        let error = serde_json::from_str::<Upload>(r#"{"content_type": "html"}"#).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("no slash in MIME type: \"html\""));
+
+        let invalid = Upload {
+            content_type: "text/".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_string(&invalid).unwrap_err().to_string(),
+            "missing MIME subtype: \"text/\""
+        );
+    }
+}
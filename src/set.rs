@@ -0,0 +1,135 @@
+//! A sorted set of MIME types.
+
+use crate::Mime;
+
+use alloc::vec::Vec;
+
+/// A set of MIME types that supports efficient membership testing.
+///
+/// The set is backed by a sorted list of MIME types, so membership is tested with a binary
+/// search. MIME types are compared using their [`Ord`] implementation, which means that
+/// parameters are taken into account. Use [`Mime::essence`] on the queried type if they should
+/// be ignored.
+///
+/// ## Example
+///
+/// ```rust
+/// use mr_mime::{Mime, MimeSet, constants};
+///
+/// let allowed = MimeSet::new(&[constants::IMAGE_PNG, constants::IMAGE_JPEG]);
+///
+/// assert!(allowed.contains(&Mime::parse("image/png").unwrap()));
+/// assert!(!allowed.contains(&constants::IMAGE_GIF));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct MimeSet {
+    /// The sorted and deduplicated MIME types.
+    mimes: Vec<Mime<'static>>,
+}
+
+impl MimeSet {
+    /// Create a new set from a list of MIME types.
+    pub fn new(mimes: &[Mime<'static>]) -> Self {
+        let mut mimes = mimes.to_vec();
+        mimes.sort_unstable();
+        mimes.dedup();
+
+        Self { mimes }
+    }
+
+    /// Create a new set containing every MIME type that the given extensions may represent.
+    ///
+    /// See [`Mime::guess`] for more information on how extensions are mapped to MIME types.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use mr_mime::{MimeSet, constants};
+    ///
+    /// let images = MimeSet::from_extensions(&["png", "jpg", "gif"]);
+    ///
+    /// assert!(images.contains(&constants::IMAGE_JPEG));
+    /// assert!(!images.contains(&constants::TEXT_HTML));
+    /// ```
+    #[cfg(feature = "tables")]
+    pub fn from_extensions(exts: &[&str]) -> Self {
+        let mimes = exts
+            .iter()
+            .flat_map(|ext| Mime::guess(ext))
+            .collect::<Vec<_>>();
+
+        Self::new(&mimes)
+    }
+
+    /// Check whether this set contains the given MIME type.
+    pub fn contains(&self, mime: &Mime<'_>) -> bool {
+        self.mimes.binary_search_by(|probe| probe.cmp(mime)).is_ok()
+    }
+
+    /// Get the number of MIME types in this set.
+    pub fn len(&self) -> usize {
+        self.mimes.len()
+    }
+
+    /// Check whether this set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.mimes.is_empty()
+    }
+
+    /// Iterate over the MIME types in this set, in sorted order.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = Mime<'static>> + '_ {
+        self.mimes.iter().map(|&mime| mime)
+    }
+}
+
+#[cfg(all(test, feature = "tables"))]
+mod mime_set_test {
+    use super::*;
+    use crate::constants;
+
+    #[test]
+    fn mime_set_sorts_and_dedups() {
+        let set = MimeSet::new(&[
+            constants::TEXT_PLAIN,
+            constants::IMAGE_PNG,
+            constants::TEXT_PLAIN,
+            constants::APPLICATION_JSON,
+        ]);
+
+        assert_eq!(set.len(), 3);
+        assert!(set.iter().eq([
+            constants::APPLICATION_JSON,
+            constants::IMAGE_PNG,
+            constants::TEXT_PLAIN
+        ]
+        .iter()
+        .copied()));
+    }
+
+    #[test]
+    fn mime_set_contains_parsed() {
+        let set = MimeSet::new(&[constants::TEXT_HTML, constants::APPLICATION_JSON]);
+
+        assert!(set.contains(&Mime::parse("TEXT/html").unwrap()));
+        assert!(set.contains(&Mime::parse("application/json").unwrap()));
+        assert!(!set.contains(&Mime::parse("text/html; charset=utf-8").unwrap()));
+        assert!(!set.contains(&Mime::parse("text/x-unknown").unwrap()));
+    }
+
+    #[test]
+    fn mime_set_empty() {
+        let set = MimeSet::new(&[]);
+
+        assert!(set.is_empty());
+        assert!(!set.contains(&constants::TEXT_PLAIN));
+    }
+
+    #[test]
+    fn mime_set_from_extensions() {
+        let set = MimeSet::from_extensions(&["html", "json", "unknown-extension"]);
+
+        assert!(set.contains(&constants::TEXT_HTML));
+        assert!(set.contains(&constants::APPLICATION_JSON));
+        assert!(!set.contains(&constants::IMAGE_PNG));
+    }
+}
@@ -0,0 +1,470 @@
+//! Detecting MIME types from the leading bytes of a file.
+//!
+//! The simple signature matching behind [`Mime::sniff`] is always available. With the `sniff`
+//! feature, this module also has [`sniff_with_context`], which follows the [MIME Sniffing
+//! Standard] that browsers use, and takes the declared type and the context of the resource into
+//! account.
+//!
+//! [MIME Sniffing Standard]: https://mimesniff.spec.whatwg.org/
+
+use crate::{constants, Mime};
+
+use core::iter::FusedIterator;
+
+#[cfg(feature = "sniff")]
+mod whatwg;
+#[cfg(feature = "sniff")]
+pub use whatwg::{sniff_with_context, SniffContext};
+
+/// A signature that identifies a file format.
+struct Signature {
+    /// The magic bytes, along with their offsets from the start of the file.
+    magic: &'static [(usize, &'static [u8])],
+
+    /// The MIME type of files that start with these bytes.
+    mime: Mime<'static>,
+}
+
+impl Signature {
+    /// Get how specific this signature is, which is the number of bytes it checks.
+    fn specificity(&self) -> usize {
+        self.magic.iter().map(|(_, magic)| magic.len()).sum()
+    }
+
+    /// Tell whether a file starting with these bytes has this signature.
+    fn matches(&self, bytes: &[u8]) -> bool {
+        self.magic.iter().all(|&(offset, magic)| {
+            bytes
+                .get(offset..)
+                .map_or(false, |rest| rest.starts_with(magic))
+        })
+    }
+}
+
+/// The most bytes that a weak signature checks.
+///
+/// Signatures this short, like the frame sync of an MP3 file, can match other files by chance.
+const WEAK_SPECIFICITY: usize = 2;
+
+/// The known signatures.
+///
+/// More specific signatures are checked first, so the ISO base media file format brands are
+/// checked before the bare `ftyp` box. Signatures that are just as specific are checked in
+/// order.
+const SIGNATURES: &[Signature] = &[
+    // Image formats
+    Signature {
+        magic: &[(0, b"\x89PNG\r\n\x1a\n")],
+        mime: constants::IMAGE_PNG,
+    },
+    Signature {
+        magic: &[(0, b"\xff\xd8\xff")],
+        mime: constants::IMAGE_JPEG,
+    },
+    Signature {
+        magic: &[(0, b"GIF87a")],
+        mime: constants::IMAGE_GIF,
+    },
+    Signature {
+        magic: &[(0, b"GIF89a")],
+        mime: constants::IMAGE_GIF,
+    },
+    Signature {
+        magic: &[(0, b"RIFF"), (8, b"WEBP")],
+        mime: constants::IMAGE_WEBP,
+    },
+    Signature {
+        // The four reserved bytes after the file size are always zero.
+        magic: &[(0, b"BM"), (6, b"\0\0\0\0")],
+        mime: constants::IMAGE_BMP,
+    },
+    Signature {
+        magic: &[(0, b"\0\0\x01\0")],
+        mime: constants::IMAGE_VND_MICROSOFT_ICON,
+    },
+    Signature {
+        magic: &[(0, b"II*\0")],
+        mime: constants::IMAGE_TIFF,
+    },
+    Signature {
+        magic: &[(0, b"MM\0*")],
+        mime: constants::IMAGE_TIFF,
+    },
+    Signature {
+        magic: &[(0, b"8BPS")],
+        mime: constants::IMAGE_VND_ADOBE_PHOTOSHOP,
+    },
+    // Documents and archives
+    Signature {
+        magic: &[(0, b"%PDF-")],
+        mime: constants::APPLICATION_PDF,
+    },
+    Signature {
+        magic: &[(0, b"PK\x03\x04")],
+        mime: constants::APPLICATION_ZIP,
+    },
+    Signature {
+        magic: &[(0, b"PK\x05\x06")],
+        mime: constants::APPLICATION_ZIP,
+    },
+    Signature {
+        magic: &[(0, b"PK\x07\x08")],
+        mime: constants::APPLICATION_ZIP,
+    },
+    Signature {
+        magic: &[(0, b"7z\xbc\xaf\x27\x1c")],
+        mime: constants::APPLICATION_X_7Z_COMPRESSED,
+    },
+    Signature {
+        magic: &[(0, b"Rar!\x1a\x07")],
+        mime: constants::APPLICATION_VND_RAR,
+    },
+    Signature {
+        magic: &[(0, b"BZh")],
+        mime: constants::APPLICATION_X_BZIP2,
+    },
+    Signature {
+        // Deflate is the only compression method.
+        magic: &[(0, b"\x1f\x8b\x08")],
+        mime: constants::APPLICATION_GZIP,
+    },
+    Signature {
+        magic: &[(0, b"\x28\xb5\x2f\xfd")],
+        mime: constants::APPLICATION_ZSTD,
+    },
+    Signature {
+        magic: &[(0, b"\xfd7zXZ\0")],
+        mime: constants::APPLICATION_X_XZ,
+    },
+    Signature {
+        magic: &[(257, b"ustar")],
+        mime: constants::APPLICATION_X_TAR,
+    },
+    // Audio and video
+    Signature {
+        magic: &[(0, b"ID3")],
+        mime: constants::AUDIO_MPEG,
+    },
+    Signature {
+        magic: &[(0, b"\xff\xfb")],
+        mime: constants::AUDIO_MPEG,
+    },
+    Signature {
+        magic: &[(0, b"\xff\xf3")],
+        mime: constants::AUDIO_MPEG,
+    },
+    Signature {
+        magic: &[(0, b"\xff\xf2")],
+        mime: constants::AUDIO_MPEG,
+    },
+    Signature {
+        magic: &[(0, b"OggS")],
+        mime: constants::APPLICATION_OGG,
+    },
+    Signature {
+        magic: &[(0, b"fLaC")],
+        mime: constants::AUDIO_FLAC,
+    },
+    Signature {
+        magic: &[(0, b"RIFF"), (8, b"WAVE")],
+        mime: constants::AUDIO_X_WAV,
+    },
+    Signature {
+        magic: &[(0, b"MThd")],
+        mime: constants::AUDIO_MIDI,
+    },
+    Signature {
+        magic: &[(4, b"ftypavif")],
+        mime: constants::IMAGE_AVIF,
+    },
+    Signature {
+        magic: &[(4, b"ftypheic")],
+        mime: constants::IMAGE_HEIC,
+    },
+    Signature {
+        magic: &[(4, b"ftypheix")],
+        mime: constants::IMAGE_HEIC,
+    },
+    Signature {
+        magic: &[(4, b"ftypM4A ")],
+        mime: constants::AUDIO_MP4,
+    },
+    Signature {
+        magic: &[(4, b"ftypqt  ")],
+        mime: constants::VIDEO_QUICKTIME,
+    },
+    Signature {
+        magic: &[(4, b"ftyp")],
+        mime: constants::VIDEO_MP4,
+    },
+    // Fonts and executables
+    Signature {
+        magic: &[(0, b"wOFF")],
+        mime: constants::FONT_WOFF,
+    },
+    Signature {
+        magic: &[(0, b"wOF2")],
+        mime: constants::FONT_WOFF2,
+    },
+    Signature {
+        magic: &[(0, b"OTTO")],
+        mime: constants::FONT_OTF,
+    },
+    Signature {
+        magic: &[(0, b"\0\x01\0\0\0")],
+        mime: constants::FONT_TTF,
+    },
+    Signature {
+        magic: &[(0, b"\x7fELF")],
+        mime: constants::APPLICATION_X_EXECUTABLE,
+    },
+    Signature {
+        magic: &[(0, b"\0asm")],
+        mime: constants::APPLICATION_WASM,
+    },
+];
+
+/// Detect the MIME type of a file from its leading bytes.
+pub(crate) fn sniff(bytes: &[u8]) -> Option<Mime<'static>> {
+    MagicGuesses::new(bytes).next()
+}
+
+/// Detect the MIME type of a file from its leading bytes, unless only a weak signature matches.
+pub(crate) fn sniff_strong(bytes: &[u8]) -> Option<Mime<'static>> {
+    let mut guesses = MagicGuesses::new(bytes);
+    let mime = guesses.next()?;
+
+    // The iterator stops at the specificity of the signature that matched.
+    if guesses.specificity > WEAK_SPECIFICITY {
+        Some(mime)
+    } else {
+        None
+    }
+}
+
+/// Iterator over the MIME types whose signatures match the leading bytes of a file.
+///
+/// The MIME types come from the most to the least specific signature, where a signature that
+/// checks more bytes is more specific. This is returned by [`Mime::guess_from_magic_bytes`].
+#[derive(Debug, Clone)]
+pub struct MagicGuesses<'a> {
+    /// The leading bytes of the file.
+    bytes: &'a [u8],
+
+    /// The specificity of the signatures that are being checked.
+    specificity: usize,
+
+    /// The index of the next signature to check.
+    index: usize,
+}
+
+impl<'a> MagicGuesses<'a> {
+    /// Check the given bytes against every signature.
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            specificity: SIGNATURES
+                .iter()
+                .map(Signature::specificity)
+                .max()
+                .unwrap_or(0),
+            index: 0,
+        }
+    }
+}
+
+impl Iterator for MagicGuesses<'_> {
+    type Item = Mime<'static>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Go over the signatures once for every specificity, from the highest down.
+        while self.specificity > 0 {
+            while let Some(signature) = SIGNATURES.get(self.index) {
+                self.index += 1;
+
+                if signature.specificity() == self.specificity && signature.matches(self.bytes) {
+                    return Some(signature.mime);
+                }
+            }
+
+            self.specificity -= 1;
+            self.index = 0;
+        }
+
+        None
+    }
+}
+
+impl FusedIterator for MagicGuesses<'_> {}
+
+#[cfg(test)]
+mod sniff_test {
+    use super::*;
+
+    #[test]
+    fn sniffs_known_signatures() {
+        for &(bytes, mime) in &[
+            (&b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"[..], constants::IMAGE_PNG),
+            (b"\xff\xd8\xff\xe0\0\x10JFIF", constants::IMAGE_JPEG),
+            (b"GIF87a\x01\0\x01\0", constants::IMAGE_GIF),
+            (b"GIF89a\x01\0\x01\0", constants::IMAGE_GIF),
+            (b"RIFF\x24\0\0\0WEBPVP8 ", constants::IMAGE_WEBP),
+            (b"BM\x46\0\0\0\0\0\0\0\x36\0", constants::IMAGE_BMP),
+            (
+                b"\0\0\x01\0\x01\0\x10\x10",
+                constants::IMAGE_VND_MICROSOFT_ICON,
+            ),
+            (b"II*\0\x08\0\0\0", constants::IMAGE_TIFF),
+            (b"MM\0*\0\0\0\x08", constants::IMAGE_TIFF),
+            (b"%PDF-1.7\n", constants::APPLICATION_PDF),
+            (b"PK\x03\x04\x14\0", constants::APPLICATION_ZIP),
+            (b"\x1f\x8b\x08\0", constants::APPLICATION_GZIP),
+            (b"\x28\xb5\x2f\xfd\x24\x04", constants::APPLICATION_ZSTD),
+            (b"\xfd7zXZ\0\0\x04\xe6\xd6", constants::APPLICATION_X_XZ),
+            (b"ID3\x04\0\0\0\0\0\x23", constants::AUDIO_MPEG),
+            (b"\xff\xfb\x90\x64\0", constants::AUDIO_MPEG),
+            (b"\xff\xf3\x48\xc4\0", constants::AUDIO_MPEG),
+            (b"\xff\xf2\x48\xc4\0", constants::AUDIO_MPEG),
+            (b"OggS\0\x02\0\0", constants::APPLICATION_OGG),
+            (b"fLaC\0\0\0\x22", constants::AUDIO_FLAC),
+            (b"RIFF\x24\x08\0\0WAVEfmt ", constants::AUDIO_X_WAV),
+            (b"\0\0\0\x20ftypisom\0\0\x02\0", constants::VIDEO_MP4),
+            (b"\0\0\0\x18ftypmp42\0\0\0\0", constants::VIDEO_MP4),
+            (b"\0\0\0\x1cftypavif\0\0\0\0", constants::IMAGE_AVIF),
+            (b"\0\0\0\x18ftypheic\0\0\0\0", constants::IMAGE_HEIC),
+            (b"\0\0\0\x20ftypM4A \0\0\0\0", constants::AUDIO_MP4),
+            (
+                b"\0\0\0\x14ftypqt  \x20\x05\x03\0",
+                constants::VIDEO_QUICKTIME,
+            ),
+            (b"wOFF\0\x01\0\0", constants::FONT_WOFF),
+            (b"wOF2\0\x01\0\0", constants::FONT_WOFF2),
+            (
+                b"\x7fELF\x02\x01\x01\0",
+                constants::APPLICATION_X_EXECUTABLE,
+            ),
+            (b"\0asm\x01\0\0\0", constants::APPLICATION_WASM),
+            (b"8BPS\0\x01\0\0", constants::IMAGE_VND_ADOBE_PHOTOSHOP),
+            (b"PK\x05\x06\0\0", constants::APPLICATION_ZIP),
+            (b"PK\x07\x08\0\0", constants::APPLICATION_ZIP),
+            (
+                b"7z\xbc\xaf\x27\x1c\0\x04",
+                constants::APPLICATION_X_7Z_COMPRESSED,
+            ),
+            (b"Rar!\x1a\x07\x01\0", constants::APPLICATION_VND_RAR),
+            (b"Rar!\x1a\x07\0\xcf", constants::APPLICATION_VND_RAR),
+            (b"BZh91AY&SY", constants::APPLICATION_X_BZIP2),
+            (b"MThd\0\0\0\x06", constants::AUDIO_MIDI),
+            (b"OTTO\0\x0a\0\x80", constants::FONT_OTF),
+            (b"\0\x01\0\0\0\x0f\0\x80", constants::FONT_TTF),
+        ] {
+            assert_eq!(sniff(bytes), Some(mime), "{:?}", bytes);
+        }
+    }
+
+    #[test]
+    fn sniffs_tar_archives() {
+        // The magic of a POSIX tar archive comes after the name, mode and other fields of the
+        // first header.
+        let mut header = [0; 512];
+        header[..9].copy_from_slice(b"notes.txt");
+        header[257..265].copy_from_slice(b"ustar\x0000");
+        assert_eq!(sniff(&header), Some(constants::APPLICATION_X_TAR));
+        assert_eq!(sniff(&header[..261]), None);
+    }
+
+    #[test]
+    fn magic_guesses_most_specific_first() {
+        let mut iter = MagicGuesses::new(b"\0\0\0\x1cftypavif\0\0\0\0");
+        assert_eq!(iter.next(), Some(constants::IMAGE_AVIF));
+        assert_eq!(iter.next(), Some(constants::VIDEO_MP4));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next(), None);
+
+        let mut iter = MagicGuesses::new(b"RIFF\x24\x08\0\0WAVEfmt ");
+        assert_eq!(iter.next(), Some(constants::AUDIO_X_WAV));
+        assert_eq!(iter.next(), None);
+
+        assert_eq!(MagicGuesses::new(b"").next(), None);
+        assert_eq!(MagicGuesses::new(b"hello, world").count(), 0);
+    }
+
+    #[test]
+    fn magic_guesses_agree_with_sniff() {
+        for signature in SIGNATURES {
+            let mut bytes = [0; 512];
+            for &(offset, magic) in signature.magic {
+                bytes[offset..offset + magic.len()].copy_from_slice(magic);
+            }
+
+            let mut iter = MagicGuesses::new(&bytes);
+            assert_eq!(iter.clone().next(), sniff(&bytes));
+            assert!(
+                iter.any(|mime| mime == signature.mime),
+                "{}",
+                signature.mime
+            );
+        }
+    }
+
+    #[test]
+    fn sniffs_nothing_from_unknown_bytes() {
+        assert_eq!(sniff(b""), None);
+        assert_eq!(sniff(b"\x89PN"), None);
+        assert_eq!(sniff(b"hello, world"), None);
+        assert_eq!(sniff(b"RIFF\x24\0\0\0AVI LIST"), None);
+        assert_eq!(sniff(b"\0\0\0\0\0\0\0\0WEBP"), None);
+        assert_eq!(sniff(b"\0\0\0\x20ftx"), None);
+        assert_eq!(sniff(b"BMW service notes"), None);
+        assert_eq!(sniff(b"\x1f\x8b\x07\0"), None);
+    }
+
+    #[test]
+    fn strong_sniffing_skips_weak_signatures() {
+        assert_eq!(sniff(b"\xff\xfb\x90\x64\0"), Some(constants::AUDIO_MPEG));
+        assert_eq!(sniff_strong(b"\xff\xfb\x90\x64\0"), None);
+        assert_eq!(sniff_strong(b"\xff\xf3\x48\xc4\0"), None);
+        assert_eq!(
+            sniff_strong(b"ID3\x04\0\0\0\0\0\x23"),
+            Some(constants::AUDIO_MPEG)
+        );
+        assert_eq!(
+            sniff_strong(b"BM\x46\0\0\0\0\0\0\0\x36\0"),
+            Some(constants::IMAGE_BMP)
+        );
+        assert_eq!(sniff_strong(b"hello"), None);
+
+        // Every signature but the MP3 frame syncs is strong.
+        let frame_syncs: [&[u8]; 3] = [b"\xff\xfb", b"\xff\xf3", b"\xff\xf2"];
+        for signature in SIGNATURES {
+            assert_eq!(
+                signature.specificity() <= WEAK_SPECIFICITY,
+                frame_syncs.contains(&signature.magic[0].1),
+                "{}",
+                signature.mime
+            );
+        }
+    }
+
+    #[test]
+    fn sniffs_nothing_from_random_bytes() {
+        // A fixed xorshift sequence, so the test is the same on every run.
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut bytes = [0; 512];
+        for byte in bytes.iter_mut() {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            *byte = state as u8;
+        }
+
+        for start in 0..bytes.len() - 16 {
+            assert_eq!(
+                sniff(&bytes[start..]),
+                None,
+                "{:?}",
+                &bytes[start..start + 16]
+            );
+        }
+    }
+}
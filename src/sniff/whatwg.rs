@@ -0,0 +1,853 @@
+//! The MIME Sniffing Standard, as browsers implement it.
+//!
+//! Section numbers in the comments refer to <https://mimesniff.spec.whatwg.org/>.
+
+use crate::{constants, Mime};
+
+/// The most bytes of a resource that the standard ever looks at.
+const RESOURCE_HEADER_LEN: usize = 1445;
+
+/// The context that a resource is going to be used in.
+///
+/// The context decides which formats a resource may be sniffed as, and how much the declared
+/// type is trusted. See [`sniff_with_context`] for the details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SniffContext {
+    /// The resource is going to be shown as a document, as in a browser tab.
+    ///
+    /// This runs the full algorithm for determining the computed MIME type of a resource.
+    Browsing,
+
+    /// The resource is going to be shown as an image, as in an `<img>` element.
+    Image,
+
+    /// The resource is going to be played as audio or video, as in a `<video>` element.
+    AudioOrVideo,
+
+    /// The resource is going to be used as a font, as in an `@font-face` rule.
+    Font,
+
+    /// The resource is going to be shown as a document, but its declared type may be the
+    /// `text/plain` that some servers send for every file they don't know.
+    ///
+    /// This is the same as [`SniffContext::Browsing`], except that the declared type is only
+    /// used to decide whether the resource is text or binary. Browsers do this when the
+    /// `Content-Type` header is exactly one of the defaults of old Apache servers, and
+    /// [`SniffContext::Browsing`] already checks for those.
+    TextOrBinary,
+}
+
+/// A byte pattern from the pattern tables of the standard (section 6).
+struct Pattern {
+    /// The bytes to match, after masking.
+    bytes: &'static [u8],
+
+    /// The mask to apply to each byte of the input before comparing it, or an empty slice to
+    /// compare the bytes exactly.
+    mask: &'static [u8],
+
+    /// The MIME type of resources that match.
+    mime: Mime<'static>,
+}
+
+impl Pattern {
+    /// Tell whether the input starts with this pattern.
+    fn matches(&self, input: &[u8]) -> bool {
+        input.len() >= self.bytes.len()
+            && self
+                .bytes
+                .iter()
+                .enumerate()
+                .all(|(i, &byte)| input[i] & self.mask.get(i).map_or(0xff, |&mask| mask) == byte)
+    }
+}
+
+/// Shorthand for a pattern without a mask.
+const fn exact(bytes: &'static [u8], mime: Mime<'static>) -> Pattern {
+    Pattern {
+        bytes,
+        mask: &[],
+        mime,
+    }
+}
+
+/// Find the MIME type of the first pattern that matches the input.
+fn match_patterns(patterns: &[Pattern], input: &[u8]) -> Option<Mime<'static>> {
+    patterns
+        .iter()
+        .find(|pattern| pattern.matches(input))
+        .map(|pattern| pattern.mime)
+}
+
+/// The tags that mark a resource as HTML when they start it (section 7.1).
+///
+/// Each of them has to be followed by a space or a `>`, and they're compared
+/// case-insensitively.
+const HTML_TAGS: &[&[u8]] = &[
+    b"<!DOCTYPE HTML",
+    b"<HTML",
+    b"<HEAD",
+    b"<SCRIPT",
+    b"<IFRAME",
+    b"<H1",
+    b"<DIV",
+    b"<FONT",
+    b"<TABLE",
+    b"<A",
+    b"<STYLE",
+    b"<TITLE",
+    b"<B",
+    b"<BODY",
+    b"<BR",
+    b"<P",
+    b"<!--",
+];
+
+/// The patterns that identify text without a scriptable type (section 7.1).
+const TEXT_PATTERNS: &[Pattern] = &[
+    exact(b"%!PS-Adobe-", constants::APPLICATION_POSTSCRIPT),
+    Pattern {
+        bytes: b"\xfe\xff\0\0",
+        mask: b"\xff\xff\0\0",
+        mime: constants::TEXT_PLAIN,
+    },
+    Pattern {
+        bytes: b"\xff\xfe\0\0",
+        mask: b"\xff\xff\0\0",
+        mime: constants::TEXT_PLAIN,
+    },
+    Pattern {
+        bytes: b"\xef\xbb\xbf\0",
+        mask: b"\xff\xff\xff\0",
+        mime: constants::TEXT_PLAIN,
+    },
+];
+
+/// The image type patterns (section 6.1).
+const IMAGE_PATTERNS: &[Pattern] = &[
+    exact(b"\0\0\x01\0", constants::IMAGE_X_ICON),
+    exact(b"\0\0\x02\0", constants::IMAGE_X_ICON),
+    exact(b"BM", constants::IMAGE_BMP),
+    exact(b"GIF87a", constants::IMAGE_GIF),
+    exact(b"GIF89a", constants::IMAGE_GIF),
+    Pattern {
+        bytes: b"RIFF\0\0\0\0WEBPVP",
+        mask: b"\xff\xff\xff\xff\0\0\0\0\xff\xff\xff\xff\xff\xff",
+        mime: constants::IMAGE_WEBP,
+    },
+    exact(b"\x89PNG\r\n\x1a\n", constants::IMAGE_PNG),
+    exact(b"\xff\xd8\xff", constants::IMAGE_JPEG),
+];
+
+/// The audio and video type patterns (section 6.2).
+const AUDIO_OR_VIDEO_PATTERNS: &[Pattern] = &[
+    Pattern {
+        bytes: b"FORM\0\0\0\0AIFF",
+        mask: b"\xff\xff\xff\xff\0\0\0\0\xff\xff\xff\xff",
+        mime: constants::AUDIO_AIFF,
+    },
+    exact(b"ID3", constants::AUDIO_MPEG),
+    exact(b"OggS\0", constants::APPLICATION_OGG),
+    exact(b"MThd\0\0\0\x06", constants::AUDIO_MIDI),
+    Pattern {
+        bytes: b"RIFF\0\0\0\0AVI ",
+        mask: b"\xff\xff\xff\xff\0\0\0\0\xff\xff\xff\xff",
+        mime: constants::VIDEO_AVI,
+    },
+    Pattern {
+        bytes: b"RIFF\0\0\0\0WAVE",
+        mask: b"\xff\xff\xff\xff\0\0\0\0\xff\xff\xff\xff",
+        mime: constants::AUDIO_WAVE,
+    },
+];
+
+/// The font type patterns (section 6.3).
+const FONT_PATTERNS: &[Pattern] = &[
+    Pattern {
+        bytes: b"\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0LP",
+        mask: b"\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\xff\xff",
+        mime: constants::APPLICATION_VND_MS_FONTOBJECT,
+    },
+    exact(b"\0\x01\0\0", constants::FONT_TTF),
+    exact(b"OTTO", constants::FONT_OTF),
+    exact(b"ttcf", constants::FONT_COLLECTION),
+    exact(b"wOFF", constants::FONT_WOFF),
+    exact(b"wOF2", constants::FONT_WOFF2),
+];
+
+/// The archive type patterns (section 6.4).
+const ARCHIVE_PATTERNS: &[Pattern] = &[
+    exact(b"\x1f\x8b\x08", constants::APPLICATION_X_GZIP),
+    exact(b"PK\x03\x04", constants::APPLICATION_ZIP),
+    // The space is what the standard says, even though RAR files actually start with "Rar!".
+    exact(b"Rar \x1a\x07\0", constants::APPLICATION_X_RAR_COMPRESSED),
+];
+
+/// Sniff the MIME type of a resource in the given context, following the [MIME Sniffing
+/// Standard].
+///
+/// `declared` is the type that the resource was sent with, if any, and `body_prefix` is the
+/// start of its body. Only the first 1445 bytes are looked at, so there's no need to pass more.
+/// `no_sniff` is set when the resource was sent with `X-Content-Type-Options: nosniff`.
+///
+/// Whether the declared type is authoritative depends on the context:
+///
+/// - In [`SniffContext::Browsing`], a missing declared type, or one of `unknown/unknown`,
+///   `application/unknown` and `*/*`, is always sniffed, and scriptable types like HTML are
+///   only detected if `no_sniff` isn't set. Otherwise, the declared type is returned as is if
+///   `no_sniff` is set or if it's an XML type. A declared `text/plain` as sent by old Apache
+///   servers only decides between text and binary, `text/html` may turn out to be an RSS or
+///   Atom feed, and image, audio and video types may be replaced by the type that the body
+///   actually has.
+/// - In [`SniffContext::Image`], [`SniffContext::AudioOrVideo`] and [`SniffContext::Font`], the
+///   body is matched against the patterns for that kind of resource, unless the declared type
+///   is an XML type or `no_sniff` is set.
+/// - In [`SniffContext::TextOrBinary`], the body is always checked for being text or binary,
+///   unless the declared type is missing or unknown, or `no_sniff` is set, as in
+///   [`SniffContext::Browsing`].
+///
+/// When nothing is declared and nothing is detected, the result is
+/// `application/octet-stream`. All image, audio and video types count as supported.
+///
+/// This is only available with the `sniff` feature.
+///
+/// [MIME Sniffing Standard]: https://mimesniff.spec.whatwg.org/
+///
+/// ## Example
+///
+/// ```rust
+/// use mr_mime::sniff::{sniff_with_context, SniffContext};
+/// use mr_mime::{constants, Mime};
+///
+/// let html = b"<!DOCTYPE html><title>Hi</title>";
+/// assert_eq!(
+///     sniff_with_context(None, html, SniffContext::Browsing, false),
+///     constants::TEXT_HTML
+/// );
+///
+/// // A nosniff header keeps the declared type, however wrong it looks.
+/// let declared = Mime::parse("application/json").unwrap();
+/// assert_eq!(
+///     sniff_with_context(Some(declared), html, SniffContext::Browsing, true),
+///     constants::APPLICATION_JSON
+/// );
+///
+/// // In an image context, the body wins over the declared type.
+/// let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+/// assert_eq!(
+///     sniff_with_context(Some(constants::IMAGE_JPEG), png, SniffContext::Image, false),
+///     constants::IMAGE_PNG
+/// );
+/// ```
+pub fn sniff_with_context<'a>(
+    declared: Option<Mime<'a>>,
+    body_prefix: &[u8],
+    ctx: SniffContext,
+    no_sniff: bool,
+) -> Mime<'a> {
+    let header = &body_prefix[..body_prefix.len().min(RESOURCE_HEADER_LEN)];
+    let fallback = declared.unwrap_or(constants::APPLICATION_OCTET_STREAM);
+    let context_patterns = match ctx {
+        SniffContext::Browsing | SniffContext::TextOrBinary => None,
+        SniffContext::Image => Some(IMAGE_PATTERNS),
+        SniffContext::AudioOrVideo => Some(AUDIO_OR_VIDEO_PATTERNS),
+        SniffContext::Font => Some(FONT_PATTERNS),
+    };
+
+    // Section 8: the contexts other than browsing only look for their own kind of resource.
+    if let Some(patterns) = context_patterns {
+        if no_sniff || declared.map_or(false, |mime| is_xml(&mime)) {
+            return fallback;
+        }
+
+        let sniffed = match ctx {
+            SniffContext::AudioOrVideo => sniff_audio_or_video(header),
+            _ => match_patterns(patterns, header),
+        };
+        return sniffed.unwrap_or(fallback);
+    }
+
+    // Section 7.1: determining the computed MIME type of a resource.
+    let declared = match declared {
+        Some(declared) if !is_unknown(&declared) => declared,
+        _ => return sniff_unknown(header, !no_sniff),
+    };
+
+    if no_sniff {
+        declared
+    } else if ctx == SniffContext::TextOrBinary || is_apache_default(&declared) {
+        sniff_text_or_binary(header)
+    } else if is_xml(&declared) {
+        declared
+    } else if declared.r#type() == "text" && declared.subtype() == "html" {
+        sniff_feed_or_html(header).unwrap_or(declared)
+    } else if declared.r#type() == "image" {
+        match_patterns(IMAGE_PATTERNS, header).unwrap_or(declared)
+    } else if is_audio_or_video(&declared) {
+        sniff_audio_or_video(header).unwrap_or(declared)
+    } else {
+        declared
+    }
+}
+
+/// Tell whether a declared type says that the type is unknown.
+fn is_unknown(mime: &Mime<'_>) -> bool {
+    let (ty, subtype) = (mime.r#type(), mime.subtype());
+    (subtype == "unknown" && (ty == "unknown" || ty == "application"))
+        || (ty == "*" && subtype == "*")
+}
+
+/// Tell whether a MIME type is an XML MIME type, as the standard defines it (section 4.6).
+fn is_xml(mime: &Mime<'_>) -> bool {
+    mime.suffix().map_or(false, |suffix| suffix == "xml")
+        || (mime.subtype() == "xml" && (mime.r#type() == "text" || mime.r#type() == "application"))
+}
+
+/// Tell whether a MIME type is an audio or video MIME type (section 4.6).
+fn is_audio_or_video(mime: &Mime<'_>) -> bool {
+    mime.r#type() == "audio"
+        || mime.r#type() == "video"
+        || (mime.r#type() == "application" && mime.subtype() == "ogg" && mime.suffix().is_none())
+}
+
+/// Tell whether a declared type is one of the `text/plain` defaults that old Apache servers
+/// sent for every file.
+///
+/// Browsers compare the header byte for byte. A parsed MIME type can't tell how its names were
+/// spelled, so only the parameters are compared exactly.
+fn is_apache_default(mime: &Mime<'_>) -> bool {
+    if mime.r#type() != "text" || mime.subtype() != "plain" || mime.suffix().is_some() {
+        return false;
+    }
+
+    let mut parameters = mime.parameters();
+    match (parameters.next(), parameters.next()) {
+        (None, _) => true,
+        (Some(("charset", value)), None) => {
+            matches!(value, b"ISO-8859-1" | b"iso-8859-1" | b"UTF-8")
+        }
+        _ => false,
+    }
+}
+
+/// Tell whether a byte is a whitespace byte (section 3).
+fn is_whitespace(byte: u8) -> bool {
+    matches!(byte, b'\t' | b'\n' | b'\x0c' | b'\r' | b' ')
+}
+
+/// Tell whether a byte is a binary data byte (section 3).
+fn is_binary_data(byte: u8) -> bool {
+    matches!(byte, 0x00..=0x08 | 0x0b | 0x0e..=0x1a | 0x1c..=0x1f)
+}
+
+/// The rules for identifying an unknown MIME type (section 7.1).
+fn sniff_unknown(header: &[u8], sniff_scriptable: bool) -> Mime<'static> {
+    if sniff_scriptable {
+        let start = header
+            .iter()
+            .position(|&byte| !is_whitespace(byte))
+            .unwrap_or(header.len());
+        let trimmed = &header[start..];
+
+        let is_html = HTML_TAGS.iter().any(|tag| {
+            trimmed.len() > tag.len()
+                && trimmed[..tag.len()].eq_ignore_ascii_case(tag)
+                && matches!(trimmed[tag.len()], b' ' | b'>')
+        });
+        if is_html {
+            return constants::TEXT_HTML;
+        } else if trimmed.starts_with(b"<?xml") {
+            return constants::TEXT_XML;
+        } else if header.starts_with(b"%PDF-") {
+            return constants::APPLICATION_PDF;
+        }
+    }
+
+    match_patterns(TEXT_PATTERNS, header)
+        .or_else(|| match_patterns(IMAGE_PATTERNS, header))
+        .or_else(|| sniff_audio_or_video(header))
+        .or_else(|| match_patterns(ARCHIVE_PATTERNS, header))
+        .unwrap_or_else(|| {
+            if header.iter().any(|&byte| is_binary_data(byte)) {
+                constants::APPLICATION_OCTET_STREAM
+            } else {
+                constants::TEXT_PLAIN
+            }
+        })
+}
+
+/// The rules for distinguishing if a resource is text or binary (section 7.2).
+fn sniff_text_or_binary(header: &[u8]) -> Mime<'static> {
+    if header.starts_with(b"\xfe\xff")
+        || header.starts_with(b"\xff\xfe")
+        || header.starts_with(b"\xef\xbb\xbf")
+        || !header.iter().any(|&byte| is_binary_data(byte))
+    {
+        constants::TEXT_PLAIN
+    } else {
+        sniff_unknown(header, false)
+    }
+}
+
+/// The rules for distinguishing if a resource is a feed or HTML (section 7.3).
+///
+/// Returns `None` if the resource should keep its declared `text/html` type.
+fn sniff_feed_or_html(header: &[u8]) -> Option<Mime<'static>> {
+    const RSS_NAMESPACE: &[u8] = b"http://purl.org/rss/1.0/";
+    const RDF_NAMESPACE: &[u8] = b"http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+
+    let rest = |s: usize| header.get(s..).filter(|rest| !rest.is_empty());
+    let mut s = if header.starts_with(b"\xef\xbb\xbf") {
+        3
+    } else {
+        0
+    };
+
+    loop {
+        // Skip whitespace up to the next tag.
+        loop {
+            match *rest(s)?.first()? {
+                b'<' => break,
+                byte if is_whitespace(byte) => s += 1,
+                _ => return None,
+            }
+        }
+        s += 1;
+
+        let tag = rest(s)?;
+        if tag.starts_with(b"!--") {
+            s += 3;
+            while !rest(s)?.starts_with(b"-->") {
+                s += 1;
+            }
+            s += 3;
+        } else if tag.starts_with(b"!") {
+            s += 1;
+            while !rest(s)?.starts_with(b">") {
+                s += 1;
+            }
+            s += 1;
+        } else if tag.starts_with(b"?") {
+            s += 1;
+            while !rest(s)?.starts_with(b"?>") {
+                s += 1;
+            }
+            s += 2;
+        } else if tag.starts_with(b"rss") {
+            return Some(constants::APPLICATION_RSS_XML);
+        } else if tag.starts_with(b"feed") {
+            return Some(constants::APPLICATION_ATOM_XML);
+        } else if tag.starts_with(b"rdf:RDF") {
+            // An RDF document is a feed if it declares both of these namespaces, in either
+            // order.
+            s += 7;
+            loop {
+                let attributes = rest(s)?;
+                let second = if attributes.starts_with(RSS_NAMESPACE) {
+                    s += RSS_NAMESPACE.len();
+                    RDF_NAMESPACE
+                } else if attributes.starts_with(RDF_NAMESPACE) {
+                    s += RDF_NAMESPACE.len();
+                    RSS_NAMESPACE
+                } else {
+                    s += 1;
+                    continue;
+                };
+
+                loop {
+                    if rest(s)?.starts_with(second) {
+                        return Some(constants::APPLICATION_RSS_XML);
+                    }
+                    s += 1;
+                }
+            }
+        } else {
+            return None;
+        }
+    }
+}
+
+/// Audio or video type pattern matching (section 6.2).
+fn sniff_audio_or_video(header: &[u8]) -> Option<Mime<'static>> {
+    match_patterns(AUDIO_OR_VIDEO_PATTERNS, header).or_else(|| {
+        if matches_mp4(header) {
+            Some(constants::VIDEO_MP4)
+        } else if matches_webm(header) {
+            Some(constants::VIDEO_WEBM)
+        } else if matches_mp3_without_id3(header) {
+            Some(constants::AUDIO_MPEG)
+        } else {
+            None
+        }
+    })
+}
+
+/// The signature for MP4 (section 6.2.1).
+fn matches_mp4(header: &[u8]) -> bool {
+    if header.len() < 12 {
+        return false;
+    }
+
+    let box_size = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    if header.len() < box_size || box_size % 4 != 0 || &header[4..8] != b"ftyp" {
+        return false;
+    }
+
+    // The major brand, and then the compatible brands after the minor version.
+    &header[8..11] == b"mp4"
+        || (16..box_size)
+            .step_by(4)
+            .any(|i| header.get(i..i + 3) == Some(b"mp4"))
+}
+
+/// The signature for WebM (section 6.2.2).
+fn matches_webm(header: &[u8]) -> bool {
+    if !header.starts_with(b"\x1a\x45\xdf\xa3") {
+        return false;
+    }
+
+    // Look for the DocType element of the EBML header.
+    let mut i = 4;
+    while i < header.len() && i < 38 {
+        if header[i..].starts_with(b"\x42\x82") {
+            i += 2;
+            if i >= header.len() {
+                return false;
+            }
+
+            i += vint_size(&header[i..]);
+            if i >= header.len() {
+                return false;
+            }
+
+            // The value may be padded with zeros in front.
+            let start = header[i..]
+                .iter()
+                .position(|&byte| byte != 0)
+                .map_or(header.len(), |start| i + start);
+            if header[start..].starts_with(b"webm") {
+                return true;
+            }
+        }
+
+        i += 1;
+    }
+
+    false
+}
+
+/// Get the size of the variable-length integer at the start of the input (section 6.2.2.1).
+fn vint_size(input: &[u8]) -> usize {
+    let mut mask = 0x80;
+    let mut size = 1;
+    while size < 8 && size < input.len() && input[0] & mask == 0 {
+        mask >>= 1;
+        size += 1;
+    }
+
+    size
+}
+
+/// The bit rates of MPEG-1 layer 3 frames, by index (section 6.2.3).
+const MP3_RATES: [u32; 15] = [
+    0, 32000, 40000, 48000, 56000, 64000, 80000, 96000, 112000, 128000, 160000, 192000, 224000,
+    256000, 320000,
+];
+
+/// The bit rates of MPEG-2 and MPEG-2.5 layer 3 frames, by index (section 6.2.3).
+const MP25_RATES: [u32; 15] = [
+    0, 8000, 16000, 24000, 32000, 40000, 48000, 56000, 64000, 80000, 96000, 112000, 128000, 144000,
+    160000,
+];
+
+/// The sample rates of MP3 frames, by index (section 6.2.3).
+const SAMPLE_RATES: [u32; 3] = [44100, 48000, 32000];
+
+/// The signature for MP3 without ID3 (section 6.2.3).
+///
+/// This needs two frame headers in a row, where the first one says the second one is.
+fn matches_mp3_without_id3(header: &[u8]) -> bool {
+    if !matches_mp3_header(header) {
+        return false;
+    }
+
+    let version = (header[1] & 0x18) >> 3;
+    let bit_rate_index = usize::from((header[2] & 0xf0) >> 4);
+    let bit_rate = if version & 0x01 != 0 {
+        MP3_RATES[bit_rate_index]
+    } else {
+        MP25_RATES[bit_rate_index]
+    };
+    let sample_rate = SAMPLE_RATES[usize::from((header[2] & 0x0c) >> 2)];
+    let padding = u32::from((header[2] & 0x02) >> 1);
+
+    let scale = if version == 1 { 72 } else { 144 };
+    let frame_size = (scale * bit_rate / sample_rate + padding) as usize;
+    if frame_size < 4 || frame_size > header.len() {
+        return false;
+    }
+
+    matches_mp3_header(&header[frame_size..])
+}
+
+/// Match an MP3 frame header at the start of the input (section 6.2.3).
+fn matches_mp3_header(input: &[u8]) -> bool {
+    input.len() >= 4
+        && input[0] == 0xff
+        && input[1] & 0xe0 == 0xe0
+        && (input[1] & 0x06) >> 1 != 0
+        && (input[2] & 0xf0) >> 4 != 15
+        && (input[2] & 0x0c) >> 2 != 3
+}
+
+#[cfg(test)]
+mod whatwg_test {
+    use super::*;
+
+    use SniffContext::*;
+
+    #[test]
+    fn sniffs_by_context() {
+        let html = Some(constants::TEXT_HTML);
+        let plain = Some(constants::TEXT_PLAIN);
+        let json = Some(constants::APPLICATION_JSON);
+        let svg = Some(constants::IMAGE_SVG_XML);
+        let jpeg = Some(constants::IMAGE_JPEG);
+        let utf8 = Mime::parse("text/plain; charset=utf-8").unwrap();
+        let unknown = Some(Mime::parse("application/unknown").unwrap());
+        let any = Some(Mime::parse("*/*").unwrap());
+
+        let png = &b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"[..];
+        let gif = &b"GIF89a\x01\0\x01\0"[..];
+        let webp = &b"RIFF\x24\0\0\0WEBPVP8 "[..];
+        let wave = &b"RIFF\x24\x08\0\0WAVEfmt "[..];
+        let mp4 = &b"\0\0\0\x18ftypmp42\0\0\0\0mp42isom"[..];
+        let woff2 = &b"wOF2\0\x01\0\0"[..];
+        let binary = &b"\0\x01\x02\x03\x04"[..];
+
+        let cases: &[(Option<Mime<'_>>, &[u8], SniffContext, Mime<'_>)] = &[
+            // Unknown types are always sniffed in a browsing context.
+            (None, b"  <!DOCTYPE html>", Browsing, constants::TEXT_HTML),
+            (None, b"\n<HTML><body>", Browsing, constants::TEXT_HTML),
+            (None, b"<script src=a.js>", Browsing, constants::TEXT_HTML),
+            (None, b"<p>hi", Browsing, constants::TEXT_HTML),
+            (None, b"<!-- x -->", Browsing, constants::TEXT_HTML),
+            (None, b"<pre>hi", Browsing, constants::TEXT_PLAIN),
+            (None, b"<html", Browsing, constants::TEXT_PLAIN),
+            (None, b"\t<?xml version=\"1.0\"?>", Browsing, constants::TEXT_XML),
+            (None, b"%PDF-1.7\n", Browsing, constants::APPLICATION_PDF),
+            (any, b"%!PS-Adobe-3.0", Browsing, constants::APPLICATION_POSTSCRIPT),
+            (unknown, b"\xfe\xff\0h", Browsing, constants::TEXT_PLAIN),
+            (None, b"\xff\xfeh\0", Browsing, constants::TEXT_PLAIN),
+            (None, b"\xef\xbb\xbfhi", Browsing, constants::TEXT_PLAIN),
+            (None, png, Browsing, constants::IMAGE_PNG),
+            (None, b"\0\0\x02\0\x01\0", Browsing, constants::IMAGE_X_ICON),
+            (None, webp, Browsing, constants::IMAGE_WEBP),
+            (None, b"FORM\0\0\0\0AIFFCOMM", Browsing, constants::AUDIO_AIFF),
+            (None, b"MThd\0\0\0\x06\0\x01", Browsing, constants::AUDIO_MIDI),
+            (None, b"RIFF\0\0\0\0AVI LIST", Browsing, constants::VIDEO_AVI),
+            (None, wave, Browsing, constants::AUDIO_WAVE),
+            (None, b"OggS\0\x02", Browsing, constants::APPLICATION_OGG),
+            (None, b"ID3\x04\0", Browsing, constants::AUDIO_MPEG),
+            (None, mp4, Browsing, constants::VIDEO_MP4),
+            (None, b"\x1f\x8b\x08\0", Browsing, constants::APPLICATION_X_GZIP),
+            (None, b"PK\x03\x04\x14\0", Browsing, constants::APPLICATION_ZIP),
+            (
+                None,
+                b"Rar \x1a\x07\0",
+                Browsing,
+                constants::APPLICATION_X_RAR_COMPRESSED,
+            ),
+            (None, b"hello, world", Browsing, constants::TEXT_PLAIN),
+            (None, b"", Browsing, constants::TEXT_PLAIN),
+            (None, binary, Browsing, constants::APPLICATION_OCTET_STREAM),
+            // Fonts are only detected in a font context.
+            (None, woff2, Browsing, constants::APPLICATION_OCTET_STREAM),
+            // Known types are kept, unless the body says otherwise in a way the standard trusts.
+            (json, b"<html>", Browsing, constants::APPLICATION_JSON),
+            (svg, png, Browsing, constants::IMAGE_SVG_XML),
+            (jpeg, png, Browsing, constants::IMAGE_PNG),
+            (jpeg, b"not an image", Browsing, constants::IMAGE_JPEG),
+            (
+                Some(constants::VIDEO_WEBM),
+                mp4,
+                Browsing,
+                constants::VIDEO_MP4,
+            ),
+            (
+                Some(constants::AUDIO_MPEG),
+                gif,
+                Browsing,
+                constants::AUDIO_MPEG,
+            ),
+            // text/html may be a feed.
+            (html, b"<html>", Browsing, constants::TEXT_HTML),
+            (
+                html,
+                b"\xef\xbb\xbf<?xml version=\"1.0\"?>\n<!-- a feed -->\n<rss version=\"2.0\">",
+                Browsing,
+                constants::APPLICATION_RSS_XML,
+            ),
+            (
+                html,
+                b"<?xml version=\"1.0\"?><!DOCTYPE feed><feed xmlns=\"http://www.w3.org/2005/Atom\">",
+                Browsing,
+                constants::APPLICATION_ATOM_XML,
+            ),
+            (
+                html,
+                b"<rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\" xmlns=\"http://purl.org/rss/1.0/\">",
+                Browsing,
+                constants::APPLICATION_RSS_XML,
+            ),
+            (
+                html,
+                b"<rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">",
+                Browsing,
+                constants::TEXT_HTML,
+            ),
+            (html, b"<rss", Browsing, constants::APPLICATION_RSS_XML),
+            (html, b"<!-- <rss", Browsing, constants::TEXT_HTML),
+            // The text/plain that Apache sends by default only means text or binary.
+            (plain, b"hello, world", Browsing, constants::TEXT_PLAIN),
+            (plain, png, Browsing, constants::IMAGE_PNG),
+            (plain, binary, Browsing, constants::APPLICATION_OCTET_STREAM),
+            (plain, b"\xff\xfe\0\x01", Browsing, constants::TEXT_PLAIN),
+            (
+                Some(Mime::parse("text/plain; charset=UTF-8").unwrap()),
+                binary,
+                Browsing,
+                constants::APPLICATION_OCTET_STREAM,
+            ),
+            (Some(utf8), binary, Browsing, utf8),
+            // Scriptable types are never sniffed when checking for text or binary.
+            (json, b"<html>", TextOrBinary, constants::TEXT_PLAIN),
+            (json, binary, TextOrBinary, constants::APPLICATION_OCTET_STREAM),
+            (json, b"%PDF-1.7\0", TextOrBinary, constants::APPLICATION_OCTET_STREAM),
+            (json, gif, TextOrBinary, constants::IMAGE_GIF),
+            (None, b"<html>", TextOrBinary, constants::TEXT_HTML),
+            // Other contexts only look for their own kind of resource.
+            (jpeg, png, Image, constants::IMAGE_PNG),
+            (None, gif, Image, constants::IMAGE_GIF),
+            (json, b"BM\x46\0", Image, constants::IMAGE_BMP),
+            (svg, png, Image, constants::IMAGE_SVG_XML),
+            (jpeg, wave, Image, constants::IMAGE_JPEG),
+            (None, wave, Image, constants::APPLICATION_OCTET_STREAM),
+            (None, wave, AudioOrVideo, constants::AUDIO_WAVE),
+            (None, mp4, AudioOrVideo, constants::VIDEO_MP4),
+            (None, png, AudioOrVideo, constants::APPLICATION_OCTET_STREAM),
+            (None, woff2, Font, constants::FONT_WOFF2),
+            (None, b"wOFF\0\x01\0\0", Font, constants::FONT_WOFF),
+            (None, b"\0\x01\0\0\0\x10", Font, constants::FONT_TTF),
+            (None, b"OTTO\0\x0b", Font, constants::FONT_OTF),
+            (None, b"ttcf\0\x01", Font, constants::FONT_COLLECTION),
+            (
+                None,
+                b"\x8c\x3d\0\0\x4c\x3d\0\0\x02\0\x02\0\x04\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0LP",
+                Font,
+                constants::APPLICATION_VND_MS_FONTOBJECT,
+            ),
+            (json, png, Font, constants::APPLICATION_JSON),
+        ];
+
+        for &(declared, bytes, ctx, expected) in cases {
+            assert_eq!(
+                sniff_with_context(declared, bytes, ctx, false),
+                expected,
+                "{:?} {:?} {:?}",
+                declared,
+                bytes,
+                ctx
+            );
+        }
+    }
+
+    #[test]
+    fn no_sniff_keeps_the_declared_type() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+        for &ctx in &[Browsing, Image, AudioOrVideo, Font, TextOrBinary] {
+            assert_eq!(
+                sniff_with_context(Some(constants::TEXT_PLAIN), png, ctx, true),
+                constants::TEXT_PLAIN
+            );
+        }
+
+        // Unknown types are still sniffed, but never as a scriptable type.
+        for declared in &[None, Some(Mime::parse("unknown/unknown").unwrap())] {
+            assert_eq!(
+                sniff_with_context(*declared, b"<html>", Browsing, true),
+                constants::TEXT_PLAIN
+            );
+            assert_eq!(
+                sniff_with_context(*declared, png, Browsing, true),
+                constants::IMAGE_PNG
+            );
+        }
+        assert_eq!(
+            sniff_with_context(None, png, Image, true),
+            constants::APPLICATION_OCTET_STREAM
+        );
+    }
+
+    #[test]
+    fn keeps_the_parameters_of_the_declared_type() {
+        let declared = Mime::parse("text/html; charset=utf-8").unwrap();
+        let sniffed = sniff_with_context(Some(declared), b"<html>", Browsing, false);
+        assert_eq!(sniffed.get_parameter("charset"), Some(b"utf-8".as_ref()));
+    }
+
+    #[test]
+    fn only_looks_at_the_resource_header() {
+        let mut body = [b'a'; RESOURCE_HEADER_LEN + 1];
+        body[RESOURCE_HEADER_LEN] = 0;
+        assert_eq!(
+            sniff_with_context(None, &body, Browsing, false),
+            constants::TEXT_PLAIN
+        );
+
+        body[RESOURCE_HEADER_LEN - 1] = 0;
+        assert_eq!(
+            sniff_with_context(None, &body, Browsing, false),
+            constants::APPLICATION_OCTET_STREAM
+        );
+    }
+
+    #[test]
+    fn sniffs_mp3_frames() {
+        // Two MPEG-1 layer 3 frames at 128 kbit/s and 44.1 kHz, which are 417 bytes long.
+        let mut frames = [0; 421];
+        frames[..4].copy_from_slice(b"\xff\xfb\x90\x64");
+        frames[417..].copy_from_slice(b"\xff\xfb\x90\x64");
+        assert_eq!(sniff_audio_or_video(&frames), Some(constants::AUDIO_MPEG));
+
+        // The second frame has to be where the first one says.
+        assert_eq!(sniff_audio_or_video(&frames[..420]), None);
+        frames[417] = 0;
+        assert_eq!(sniff_audio_or_video(&frames), None);
+    }
+
+    #[test]
+    fn sniffs_webm() {
+        let webm = b"\x1a\x45\xdf\xa3\x9f\x42\x86\x81\x01\x42\xf7\x81\x01\x42\xf2\x81\x04\
+                     \x42\xf3\x81\x08\x42\x82\x84webm\x42\x87\x81\x04";
+        assert_eq!(sniff_audio_or_video(webm), Some(constants::VIDEO_WEBM));
+
+        let matroska = b"\x1a\x45\xdf\xa3\x9f\x42\x86\x81\x01\x42\x82\x88matroska";
+        assert_eq!(sniff_audio_or_video(matroska), None);
+        assert_eq!(sniff_audio_or_video(b"\x1a\x45\xdf\xa3\x42\x82"), None);
+    }
+
+    #[test]
+    fn sniffs_mp4_brands() {
+        // The major brand isn't MP4, but one of the compatible brands is.
+        let mp4 = b"\0\0\0\x1cftypM4V \0\0\0\x01M4V M4A mp42isom";
+        assert_eq!(sniff_audio_or_video(mp4), Some(constants::VIDEO_MP4));
+
+        let quicktime = b"\0\0\0\x14ftypqt  \0\0\x02\0qt  ";
+        assert_eq!(sniff_audio_or_video(quicktime), None);
+
+        // The box has to fit in what was read.
+        assert_eq!(sniff_audio_or_video(&mp4[..24]), None);
+    }
+}
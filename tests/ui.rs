@@ -0,0 +1,20 @@
+//! Checks that MIME type literals are checked at compile time.
+//!
+//! The expected compiler output is in the `.stderr` files next to the tests, as the stable
+//! compiler writes it. Run with `TRYBUILD=overwrite` to update them when the output changes.
+
+#![cfg(feature = "tables")]
+
+#[test]
+fn literals() {
+    let cases = trybuild::TestCases::new();
+    cases.pass("tests/ui/pass/*.rs");
+    cases.compile_fail("tests/ui/fail/*.rs");
+}
+
+#[cfg(feature = "proc-macro")]
+#[test]
+fn derive() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/derive/*.rs");
+}
@@ -0,0 +1,6 @@
+#[derive(mr_mime::HasMimeType)]
+#[mime = "text/plain"]
+#[mime = "text/html"]
+struct Page;
+
+fn main() {}
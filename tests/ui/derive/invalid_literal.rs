@@ -0,0 +1,8 @@
+#[derive(mr_mime::HasMimeType)]
+#[mime = "application/octet-stream"]
+enum Asset {
+    #[mime = "image /png"]
+    Png,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[derive(mr_mime::HasMimeType)]
+enum Asset {
+    #[mime = "image/png"]
+    Png,
+}
+
+fn main() {}
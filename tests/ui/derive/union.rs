@@ -0,0 +1,7 @@
+#[derive(mr_mime::HasMimeType)]
+#[mime = "application/octet-stream"]
+union Bytes {
+    a: u8,
+}
+
+fn main() {}
@@ -0,0 +1,5 @@
+use mr_mime::Mime;
+
+const JSON: Mime<'static> = Mime::from_static("json");
+
+fn main() {}
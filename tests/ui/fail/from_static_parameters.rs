@@ -0,0 +1,5 @@
+use mr_mime::Mime;
+
+const JSON: Mime<'static> = Mime::from_static("application/json; charset=utf-8");
+
+fn main() {}
@@ -0,0 +1,3 @@
+const HTML: mr_mime::Mime<'static> = mr_mime::mime!("text/html; charset");
+
+fn main() {}
@@ -0,0 +1,3 @@
+fn main() {
+    let _ = mr_mime::mime!("multipart/form-data; boundary=\"abc");
+}
@@ -0,0 +1,3 @@
+fn main() {
+    let _ = mr_mime::mime!("text /html");
+}
@@ -0,0 +1,19 @@
+use mr_mime::{constants, mime, Mime};
+
+const PLAIN: Mime<'static> = mime!("text/plain");
+const SVG: Mime<'static> = Mime::from_static("image/svg+xml");
+const HTML: Mime<'static> = mime!("text/html; charset=utf-8");
+static TABLE: &[Mime<'static>] = &[PLAIN, SVG, HTML, mime!("application/x-custom+json")];
+
+fn main() {
+    // Known names compare equal to the interned constants, and can be interned again.
+    assert_eq!(PLAIN, constants::TEXT_PLAIN);
+    assert_eq!(SVG, constants::IMAGE_SVG_XML);
+    assert_eq!(PLAIN.try_into_static(), Ok(constants::TEXT_PLAIN));
+    assert_eq!(SVG.try_into_static(), Ok(constants::IMAGE_SVG_XML));
+    assert_eq!(HTML.essence(), constants::TEXT_HTML);
+    assert_eq!(HTML.get_parameter("charset"), Some(&b"utf-8"[..]));
+
+    assert_eq!(TABLE[3].subtype(), "x-custom");
+    assert_eq!(TABLE[3].suffix(), Some(constants::suffixes::JSON));
+}